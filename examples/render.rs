@@ -22,19 +22,19 @@ fn draw_hex(
 ) {
     let (width, height) = state.display_size;
     let dim = 0.5 * usize::min(width, height) as f32;
-    let scale_point = |(x, y): (f32, f32)| {
-        ((1.0 + x * HEXSCALE) * dim, (1.0 + y * HEXSCALE) * dim)
-    };
+    let mut camera = Camera::new(Layout::default());
+    camera.pan = (-1.0 / HEXSCALE, -1.0 / HEXSCALE);
+    camera.zoom = HEXSCALE * dim;
 
-    let coords = hex.cartesian_corners();
+    let coords = camera.screen_layout().polygon_corners(*hex);
 
     let mut pb = PathBuilder::new();
     let move_to = |pb: &mut PathBuilder, (x, y)| pb.move_to(x, y);
     let line_to = |pb: &mut PathBuilder, (x, y)| pb.line_to(x, y);
 
-    move_to(&mut pb, scale_point(coords[0]));
+    move_to(&mut pb, coords[0]);
     for &xy in &coords[1..] {
-        line_to(&mut pb, scale_point(xy));
+        line_to(&mut pb, xy);
     }
     pb.close();
     let path = pb.finish();
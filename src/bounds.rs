@@ -0,0 +1,334 @@
+use std::ops::Range;
+
+use num::{PrimInt, Signed};
+
+use crate::*;
+
+/// Parallelogram-shaped board: the hexes whose axial
+/// coordinates fall in the half-open ranges `q` and `r`. A
+/// lighter-weight alternative to a `HexMap` for checking
+/// whether a hex is on the board.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HexBounds<T> {
+    pub q: Range<T>,
+    pub r: Range<T>,
+}
+
+impl<T: PrimInt> HexBounds<T> {
+    /// Make bounds from the given coordinate ranges.
+    pub fn new(q: Range<T>, r: Range<T>) -> Self {
+        HexBounds { q, r }
+    }
+
+    /// True if `hex` is inside the bounds.
+    pub fn contains(&self, hex: HexCoord<T>) -> bool {
+        self.q.contains(&hex.q) && self.r.contains(&hex.r)
+    }
+
+    /// The in-bounds hex nearest to `hex`: `hex` itself if
+    /// it is in bounds. Each coordinate is clamped to its
+    /// range independently. This is exact because `q` and
+    /// `r` are, up to sign, the cube `x` and `z`: once both
+    /// are as close as possible, the remaining `y`
+    /// difference cannot be improved on.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bounds are empty.
+    pub fn clamp(&self, hex: HexCoord<T>) -> HexCoord<T> {
+        assert!(
+            !self.q.is_empty() && !self.r.is_empty(),
+            "clamp to empty bounds"
+        );
+        let clamp1 = |c: T, range: &Range<T>| {
+            c.max(range.start).min(range.end - T::one())
+        };
+        HexCoord::new(clamp1(hex.q, &self.q), clamp1(hex.r, &self.r))
+    }
+
+    // Number of values in `range`.
+    fn span(range: &Range<T>) -> usize {
+        if range.is_empty() {
+            return 0;
+        }
+        (range.end - range.start)
+            .to_usize()
+            .expect("bounds too large to index")
+    }
+
+    /// Number of hexes inside the bounds.
+    pub fn len(&self) -> usize {
+        Self::span(&self.q) * Self::span(&self.r)
+    }
+
+    /// True if the bounds contain no hexes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Position of `hex` in a dense row-major array of the
+    /// hexes in bounds, or `None` if `hex` is out of bounds.
+    /// Rows run along `q` at constant `r`, starting with
+    /// `r == self.r.start`, so that indices run from `0`
+    /// through `self.len() - 1`.
+    ///
+    /// These are axial rows, which slant on screen: the
+    /// bounds are an axial parallelogram. For the horizontal
+    /// rows of a tilemap-style array, use `OffsetBounds`.
+    pub fn index_of(&self, hex: HexCoord<T>) -> Option<usize> {
+        if !self.contains(hex) {
+            return None;
+        }
+        let dq = (hex.q - self.q.start).to_usize()?;
+        let dr = (hex.r - self.r.start).to_usize()?;
+        Some(dr * Self::span(&self.q) + dq)
+    }
+
+    /// The hex at position `i` in the ordering of
+    /// `index_of()`, or `None` if `i` is not less than
+    /// `self.len()`.
+    pub fn from_index(&self, i: usize) -> Option<HexCoord<T>> {
+        if i >= self.len() {
+            return None;
+        }
+        let width = Self::span(&self.q);
+        let q = self.q.start + T::from(i % width)?;
+        let r = self.r.start + T::from(i / width)?;
+        Some(HexCoord::new(q, r))
+    }
+
+    /// Multi-line picture of the hexes in bounds, for
+    /// debugging: each hex is drawn as the character `f`
+    /// gives for it, at the position of its center. North is
+    /// up, each column of hexes is two characters from the
+    /// next, and alternate columns are staggered by a line.
+    /// Lines have no trailing spaces and end with a newline.
+    ///
+    /// To draw a `HexMap<T, char>`, use something like
+    /// `|h| map.get(h).copied().unwrap_or('.')`.
+    pub fn debug_ascii<F>(&self, f: F) -> String
+    where
+        F: Fn(HexCoord<T>) -> char,
+    {
+        // Twice the Cartesian `y` of each center, in units of
+        // the hex height, is `2 * r - q` plus a constant;
+        // lines are numbered from the bottom.
+        let width = Self::span(&self.q);
+        let height = Self::span(&self.r);
+        if width == 0 || height == 0 {
+            return String::new();
+        }
+        let mut lines = vec![Vec::new(); 2 * height + width - 2];
+        for i in 0..self.len() {
+            let (dq, dr) = (i % width, i / width);
+            let line = &mut lines[2 * dr + width - 1 - dq];
+            if line.len() <= 2 * dq {
+                line.resize(2 * dq + 1, ' ');
+            }
+            line[2 * dq] = f(self.from_index(i).unwrap());
+        }
+        let mut out = String::new();
+        for line in lines.into_iter().rev() {
+            out.extend(line);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Rectangular board in offset coordinates: the hexes whose
+/// `OffsetCoord` of the given `kind` falls in the half-open
+/// ranges `cols` and `rows`. This is the layout of a
+/// tilemap-style array, with horizontal rows on screen.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OffsetBounds<T> {
+    pub cols: Range<T>,
+    pub rows: Range<T>,
+    pub kind: OffsetKind,
+}
+
+impl<T: PrimInt + Signed> OffsetBounds<T> {
+    /// Make bounds from the given offset coordinate ranges.
+    pub fn new(
+        cols: Range<T>,
+        rows: Range<T>,
+        kind: OffsetKind,
+    ) -> Self {
+        OffsetBounds { cols, rows, kind }
+    }
+
+    /// True if `hex` is inside the bounds.
+    pub fn contains(&self, hex: HexCoord<T>) -> bool {
+        let off = OffsetCoord::from_hex(hex, self.kind);
+        self.cols.contains(&off.col) && self.rows.contains(&off.row)
+    }
+
+    /// Number of hexes inside the bounds.
+    pub fn len(&self) -> usize {
+        HexBounds::span(&self.cols) * HexBounds::span(&self.rows)
+    }
+
+    /// True if the bounds contain no hexes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Position of `hex` in a dense row-major array of the
+    /// hexes in bounds, or `None` if `hex` is out of bounds.
+    /// Rows are offset rows, starting with `self.rows.start`
+    /// and each running west to east, in the order of
+    /// `shapes::rectangle_offset()`.
+    pub fn index_of(&self, hex: HexCoord<T>) -> Option<usize> {
+        if !self.contains(hex) {
+            return None;
+        }
+        let off = OffsetCoord::from_hex(hex, self.kind);
+        let col = (off.col - self.cols.start).to_usize()?;
+        let row = (off.row - self.rows.start).to_usize()?;
+        Some(row * HexBounds::span(&self.cols) + col)
+    }
+
+    /// The hex at position `i` in the ordering of
+    /// `index_of()`, or `None` if `i` is not less than
+    /// `self.len()`.
+    pub fn from_index(&self, i: usize) -> Option<HexCoord<T>> {
+        if i >= self.len() {
+            return None;
+        }
+        let width = HexBounds::span(&self.cols);
+        let col = self.cols.start + T::from(i % width)?;
+        let row = self.rows.start + T::from(i / width)?;
+        Some(OffsetCoord::new(col, row).to_hex(self.kind))
+    }
+}
+
+#[test]
+fn test_hex_bounds_contains() {
+    let bounds = HexBounds::new(-2i32..3, 0..4);
+    let h = HexCoord::new;
+    assert!(bounds.contains(h(-2, 0)));
+    assert!(bounds.contains(h(2, 3)));
+    assert!(bounds.contains(h(0, 2)));
+    assert!(!bounds.contains(h(-3, 0)));
+    assert!(!bounds.contains(h(3, 0)));
+    assert!(!bounds.contains(h(0, -1)));
+    assert!(!bounds.contains(h(0, 4)));
+}
+
+#[test]
+fn test_hex_bounds_clamp() {
+    let bounds = HexBounds::new(-2i32..3, 0..4);
+    let h = HexCoord::new;
+    assert_eq!(h(1, 1), bounds.clamp(h(1, 1)));
+    assert_eq!(h(2, 3), bounds.clamp(h(50, 40)));
+    assert_eq!(h(-2, 0), bounds.clamp(h(-9, -30)));
+
+    let inside: Vec<_> = shapes::range(h(0, 0), 10)
+        .filter(|&g| bounds.contains(g))
+        .collect();
+    for target in shapes::range(h(0, 2), 8) {
+        let clamped = bounds.clamp(target);
+        assert!(bounds.contains(clamped));
+        let best = inside.iter().map(|&g| g.distance(target)).min();
+        assert_eq!(best, Some(clamped.distance(target)));
+    }
+}
+
+#[test]
+fn test_hex_bounds_index() {
+    let bounds = HexBounds::new(-2i32..3, 1..5);
+    let h = HexCoord::new;
+    assert_eq!(20, bounds.len());
+    assert_eq!(Some(0), bounds.index_of(h(-2, 1)));
+    assert_eq!(Some(5), bounds.index_of(h(-2, 2)));
+    assert_eq!(Some(19), bounds.index_of(h(2, 4)));
+    for i in 0..bounds.len() {
+        let hex = bounds.from_index(i).unwrap();
+        assert!(bounds.contains(hex));
+        assert_eq!(Some(i), bounds.index_of(hex));
+    }
+    assert_eq!(None, bounds.from_index(20));
+    for &hex in &[h(3, 1), h(-3, 2), h(0, 0), h(0, 5)] {
+        assert_eq!(None, bounds.index_of(hex));
+    }
+
+    let empty = HexBounds::new(0i32..0, 0..4);
+    assert!(empty.is_empty());
+    assert_eq!(None, empty.from_index(0));
+}
+
+#[test]
+fn test_hex_bounds_index_order() {
+    // Axial rows of constant `r`, not offset rows: the
+    // second row starts at `(0, 1)`, northeast of the first
+    // row's start rather than directly above or below it.
+    let bounds = HexBounds::new(0i32..3, 0..2);
+    let order: Vec<_> = (0..bounds.len())
+        .map(|i| bounds.from_index(i).unwrap())
+        .collect();
+    let h = HexCoord::new;
+    let expected =
+        [h(0, 0), h(1, 0), h(2, 0), h(0, 1), h(1, 1), h(2, 1)];
+    assert_eq!(&expected[..], &order[..]);
+    let rows: Vec<_> = order
+        .iter()
+        .map(|&g| OffsetCoord::from_hex(g, OffsetKind::EvenQ).row)
+        .collect();
+    // The first axial row spans more than one offset row.
+    assert!(rows[..3].iter().any(|&row| row != rows[0]));
+}
+
+#[test]
+fn test_hex_bounds_debug_ascii() {
+    let bounds = HexBounds::new(0i32..3, 0..2);
+    let mut map = HexMap::new();
+    map.insert(HexCoord::new(0, 0), 'a');
+    map.insert(HexCoord::new(1, 1), 'b');
+    map.insert(HexCoord::new(2, 0), 'c');
+    let picture =
+        bounds.debug_ascii(|h| map.get(h).copied().unwrap_or('.'));
+    // `q` runs southeast and `r` north, so the bounds are
+    // drawn as a slanted parallelogram.
+    let expected = ".\n  b\na   .\n  .\n    c\n";
+    assert_eq!(expected, picture);
+
+    let empty = HexBounds::new(0i32..0, 0..2);
+    assert_eq!("", empty.debug_ascii(|_| '#'));
+}
+
+#[test]
+fn test_offset_bounds_index() {
+    let h = HexCoord::new;
+    for &kind in &[OffsetKind::OddQ, OffsetKind::EvenQ] {
+        let bounds = OffsetBounds::new(-2i32..3, -1..3, kind);
+        assert_eq!(20, bounds.len());
+        let (cols, rows) = (5, 4);
+        let rect: Vec<_> = shapes::rectangle_offset(cols, rows, kind)
+            .map(|(off, _)| {
+                OffsetCoord::new(off.col - 2, off.row - 1).to_hex(kind)
+            })
+            .collect();
+        for (i, &hex) in rect.iter().enumerate() {
+            assert!(bounds.contains(hex));
+            assert_eq!(Some(i), bounds.index_of(hex));
+            assert_eq!(Some(hex), bounds.from_index(i));
+        }
+        // Each row is a horizontal offset row.
+        let first = bounds.from_index(0).unwrap();
+        let fifth = bounds.from_index(4).unwrap();
+        assert_eq!(
+            OffsetCoord::from_hex(first, kind).row,
+            OffsetCoord::from_hex(fifth, kind).row
+        );
+        assert_eq!(None, bounds.from_index(20));
+        for &(col, row) in &[(3, 0), (-3, 0), (0, -2), (0, 3)] {
+            let hex = OffsetCoord::new(col, row).to_hex(kind);
+            assert!(!bounds.contains(hex));
+            assert_eq!(None, bounds.index_of(hex));
+        }
+        assert_eq!(None, bounds.index_of(h(40, 40)));
+    }
+    let empty = OffsetBounds::new(0i32..4, 2..2, OffsetKind::OddQ);
+    assert!(empty.is_empty());
+    assert_eq!(None, empty.from_index(0));
+}
@@ -0,0 +1,101 @@
+use crate::*;
+
+/// A pannable, zoomable view of a `Layout`, for mapping
+/// between hexes and screen positions in an interactive
+/// display. A layout pixel position `p` is drawn at screen
+/// position `(p - pan) * zoom`: `pan` is the layout position
+/// shown at the screen origin, and `zoom` the number of
+/// screen pixels per layout pixel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera<U> {
+    /// Placement of the grid in the unzoomed plane.
+    pub layout: Layout<U>,
+    /// Layout position shown at the screen origin.
+    pub pan: (U, U),
+    /// Screen pixels per layout pixel.
+    pub zoom: U,
+}
+
+impl<U: Float> Camera<U> {
+    /// Make an unpanned, unzoomed camera viewing `layout`.
+    pub fn new(layout: Layout<U>) -> Self {
+        Camera {
+            layout,
+            pan: (U::zero(), U::zero()),
+            zoom: U::one(),
+        }
+    }
+
+    /// The layout placing hexes directly in screen pixels:
+    /// `layout` with the camera transform applied. Use this
+    /// for drawing hex outlines with
+    /// `Layout::polygon_corners()` and the like.
+    pub fn screen_layout(&self) -> Layout<U> {
+        let Layout {
+            size,
+            origin,
+            handedness,
+        } = self.layout;
+        Layout::new(
+            (size.0 * self.zoom, size.1 * self.zoom),
+            (
+                (origin.0 - self.pan.0) * self.zoom,
+                (origin.1 - self.pan.1) * self.zoom,
+            ),
+        )
+        .with_handedness(handedness)
+    }
+
+    /// Screen position of the center of `hex`.
+    pub fn hex_to_screen<T>(&self, hex: HexCoord<T>) -> (U, U)
+    where
+        T: Num + Clone + Into<U>,
+    {
+        self.screen_layout().hex_to_pixel(hex)
+    }
+
+    /// Hex shown at screen position `p`.
+    pub fn screen_to_hex<T>(&self, p: (U, U)) -> HexCoord<T>
+    where
+        T: Num + Clone + NumCast,
+    {
+        self.screen_layout().pixel_to_hex(p)
+    }
+}
+
+#[test]
+fn test_camera_pan() {
+    let layout = Layout::new((40.0f32, -40.0), (10.0, 20.0));
+    let mut camera = Camera::new(layout);
+    let origin = HexCoord::new(0i16, 0i16);
+    let point = camera.hex_to_screen(origin);
+    assert_eq!(layout.hex_to_pixel(origin), point);
+    assert_eq!(origin, camera.screen_to_hex(point));
+
+    // Panning by the offset of a neighbor brings that
+    // neighbor under a fixed screen point.
+    for &d in &DIRECTIONS {
+        let (x0, y0) = layout.hex_to_pixel(origin);
+        let (x1, y1) = layout.hex_to_pixel(origin.neighbor(d));
+        camera.pan = (x1 - x0, y1 - y0);
+        assert_eq!(origin.neighbor(d), camera.screen_to_hex(point));
+    }
+}
+
+#[test]
+fn test_camera_zoom() {
+    let layout = Layout::new((1.0f32, 1.0), (0.0, 0.0))
+        .with_handedness(Handedness::Left);
+    let mut camera = Camera::new(layout);
+    camera.pan = (-10.0, -10.0);
+    for &zoom in &[0.5f32, 1.0, 25.0, 300.0] {
+        camera.zoom = zoom;
+        for hex in shapes::range(HexCoord::new(2i16, -1i16), 4) {
+            let (x, y) = camera.hex_to_screen(hex);
+            let (lx, ly) = layout.hex_to_pixel(hex);
+            assert!((x - (lx + 10.0) * zoom).abs() < 1e-3 * zoom);
+            assert!((y - (ly + 10.0) * zoom).abs() < 1e-3 * zoom);
+            assert_eq!(hex, camera.screen_to_hex((x, y)));
+        }
+    }
+}
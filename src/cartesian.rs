@@ -0,0 +1,465 @@
+//! Helpers relating hexes to points in the Cartesian plane
+//! of `HexCoord::cartesian_center()`.
+
+use std::collections::BTreeMap;
+use std::hash::Hash;
+
+use num::PrimInt;
+
+use crate::*;
+
+/// Distance from the center of a unit-width hex to each of
+/// its corners: `0.5`.
+pub fn circumradius<U: Float>() -> U {
+    half!(U)
+}
+
+/// Distance from the center of a unit-width hex to the
+/// midpoint of each of its edges: `sqrt(3) / 4`.
+pub fn inradius<U: Float>() -> U {
+    half!(U) * sqrt3d2!(U)
+}
+
+#[test]
+fn test_radii() {
+    let hex = HexCoord::new(-2i32, 3i32);
+    let (x, y) = hex.cartesian_center::<f64>();
+    let corners = hex.cartesian_corners::<f64>();
+    for i in 0..6 {
+        let (cx, cy) = corners[i];
+        let r = ((cx - x).powi(2) + (cy - y).powi(2)).sqrt();
+        assert!((r - circumradius::<f64>()).abs() < 1e-12);
+        let (dx, dy) = corners[(i + 1) % 6];
+        let (mx, my) = ((cx + dx) / 2.0, (cy + dy) / 2.0);
+        let r = ((mx - x).powi(2) + (my - y).powi(2)).sqrt();
+        assert!((r - inradius::<f64>()).abs() < 1e-12);
+    }
+    assert_eq!(0.5f32, circumradius());
+    assert!((inradius::<f64>() - 3.0f64.sqrt() / 4.0).abs() < 1e-15);
+}
+
+/// Index of the corner diametrically opposite corner `i`.
+/// Corner `i` is element `i` of
+/// `HexCoord::cartesian_corners()`, at `60° * i`
+/// counterclockwise from east; edge `i` runs from corner `i`
+/// to corner `i + 1` (mod 6), facing direction `i`.
+///
+/// # Panics
+///
+/// Panics if `i` is not less than `6`.
+pub fn opposite_corner(i: usize) -> usize {
+    assert!(i < 6, "corner index out of range");
+    (i + 3) % 6
+}
+
+/// The edge opposite the edge facing `d`: the one facing
+/// `d.opposite()`. See `opposite_corner()` for how edges
+/// are numbered.
+pub fn opposite_edge(d: Direction) -> Direction {
+    d.opposite()
+}
+
+#[test]
+fn test_opposites() {
+    let hex = HexCoord::new(2i32, 1i32);
+    let (x, y) = hex.cartesian_center::<f64>();
+    let corners = hex.cartesian_corners::<f64>();
+    for i in 0..6 {
+        let j = opposite_corner(i);
+        assert_eq!(i, opposite_corner(j));
+        assert_ne!(i, j);
+        // Opposite corners are symmetric about the center.
+        assert!((corners[i].0 + corners[j].0 - 2.0 * x).abs() < 1e-12);
+        assert!((corners[i].1 + corners[j].1 - 2.0 * y).abs() < 1e-12);
+    }
+    for &d in &DIRECTIONS {
+        assert_eq!(d, opposite_edge(opposite_edge(d)));
+        assert_eq!(3, d.turn_to(opposite_edge(d)));
+        assert_eq!(hex, hex.neighbor(d).neighbor(d.opposite()));
+    }
+}
+
+/// The corner shared by the three hexes `a`, `b` and `c`,
+/// or `None` unless each of them is adjacent to the other
+/// two. Three mutually adjacent centers form an equilateral
+/// triangle whose centroid is the shared corner.
+pub fn shared_corner<T, U>(
+    a: HexCoord<T>,
+    b: HexCoord<T>,
+    c: HexCoord<T>,
+) -> Option<(U, U)>
+where
+    T: Num + PartialOrd + Clone + Into<U>,
+    U: Float,
+{
+    let adjacent = |h: &HexCoord<T>, g: &HexCoord<T>| {
+        h.clone().distance(g.clone()) == T::one()
+    };
+    if !(adjacent(&a, &b) && adjacent(&b, &c) && adjacent(&c, &a)) {
+        return None;
+    }
+    let third = U::one() / nc!("3", U);
+    let (ax, ay) = a.cartesian_center::<U>();
+    let (bx, by) = b.cartesian_center::<U>();
+    let (cx, cy) = c.cartesian_center::<U>();
+    Some(((ax + bx + cx) * third, (ay + by + cy) * third))
+}
+
+#[test]
+fn test_shared_corner() {
+    let center = HexCoord::new(1i32, -2i32);
+    let corners = center.cartesian_corners::<f64>();
+    for (k, &d) in DIRECTIONS.iter().enumerate() {
+        let a = center.neighbor(d);
+        let b = center.neighbor(DIRECTIONS[(k + 1) % 6]);
+        let (x, y) = shared_corner::<_, f64>(center, a, b).unwrap();
+        let (cx, cy) = corners[(k + 1) % 6];
+        assert!((x - cx).abs() < 1e-12 && (y - cy).abs() < 1e-12);
+        assert_eq!(Some((x, y)), shared_corner(b, center, a));
+    }
+    let n = |d| center.neighbor(d);
+    use Direction::*;
+    assert_eq!(None, shared_corner::<_, f64>(center, n(N), n(S)));
+    assert_eq!(
+        None,
+        shared_corner::<_, f64>(center, n(N), n(NW).neighbor(N))
+    );
+    assert_eq!(None, shared_corner::<_, f64>(center, center, n(N)));
+}
+
+/// The hex among `hexes` whose center is closest to
+/// `point`, or `None` if `hexes` is empty. Ties go to the
+/// earliest candidate. Unlike `HexCoord::from_cartesian()`,
+/// the answer is always one of the candidates, even when
+/// the hex containing `point` is not.
+// `Option::is_none_or()`, which clippy prefers here, needs
+// Rust 1.82.
+#[allow(clippy::unnecessary_map_or)]
+pub fn nearest<T, U, I>(point: (U, U), hexes: I) -> Option<HexCoord<T>>
+where
+    T: Num + Clone + Into<U>,
+    U: Float,
+    I: IntoIterator<Item = HexCoord<T>>,
+{
+    let (px, py) = point;
+    let mut best: Option<(U, HexCoord<T>)> = None;
+    for hex in hexes {
+        let (x, y) = hex.clone().cartesian_center();
+        let d = (x - px).powi(2) + (y - py).powi(2);
+        if best.as_ref().map_or(true, |&(bd, _)| d < bd) {
+            best = Some((d, hex));
+        }
+    }
+    best.map(|(_, hex)| hex)
+}
+
+#[test]
+fn test_nearest() {
+    let h = HexCoord::new;
+    let point = (0.0f64, 0.3f64);
+    assert_eq!(h(0i32, 0i32), HexCoord::from_cartesian(point));
+
+    let candidates = vec![h(1, 0), h(0, 1), h(-1, -1)];
+    assert_eq!(Some(h(0, 1)), nearest(point, candidates.clone()));
+
+    let mut with_center = candidates;
+    with_center.push(h(0, 0));
+    assert_eq!(Some(h(0, 0)), nearest(point, with_center));
+
+    assert_eq!(None, nearest(point, Vec::<HexCoord<i32>>::new()));
+}
+
+/// Centers of `hexes`, as given by
+/// `HexCoord::cartesian_center()`, written to `out` in the
+/// same order. `out` is cleared first; reusing it across
+/// calls avoids allocation, and the scale constants are
+/// computed once per call rather than once per hex.
+pub fn cartesian_centers<T, U>(
+    hexes: &[HexCoord<T>],
+    out: &mut Vec<(U, U)>,
+) where
+    T: Num + Clone + Into<U>,
+    U: Float,
+{
+    let xscale = num_const::<U>("0.75");
+    let yscale = sqrt3d2!(U);
+    let half = half!(U);
+    out.clear();
+    out.extend(hexes.iter().map(|hex| {
+        let q: U = hex.q.clone().into();
+        let r: U = hex.r.clone().into();
+        (xscale * q, -yscale * (half * q - r))
+    }));
+}
+
+#[test]
+fn test_cartesian_centers() {
+    let hexes: Vec<_> =
+        shapes::range(HexCoord::new(2i32, -5i32), 3).collect();
+    let mut out = vec![(9.0f64, 9.0f64)];
+    cartesian_centers(&hexes, &mut out);
+    assert_eq!(hexes.len(), out.len());
+    for (&hex, &center) in hexes.iter().zip(out.iter()) {
+        assert_eq!(hex.cartesian_center::<f64>(), center);
+    }
+    cartesian_centers::<i32, f64>(&[], &mut out);
+    assert!(out.is_empty());
+}
+
+/// The hex containing `point`, as found by
+/// `HexCoord::from_cartesian()`, together with the offset
+/// `(dx, dy)` of `point` from that hex's center.
+pub fn snap<T, U>(point: (U, U)) -> (HexCoord<T>, (U, U))
+where
+    T: Num + NumCast + Clone + Into<U>,
+    U: Float,
+{
+    let hex: HexCoord<T> = HexCoord::from_cartesian(point);
+    let (x, y) = hex.clone().cartesian_center();
+    (hex, (point.0 - x, point.1 - y))
+}
+
+#[test]
+fn test_snap() {
+    let hex = HexCoord::new(2i32, -1i32);
+    let (cx, cy): (f64, f64) = hex.cartesian_center();
+    let (h, (dx, dy)) = snap::<i32, f64>((cx, cy));
+    assert_eq!(hex, h);
+    assert!(dx.abs() < 1e-9 && dy.abs() < 1e-9);
+
+    // Just inside the easternmost corner.
+    let (h, (dx, dy)) = snap::<i32, f64>((cx + 0.49, cy + 0.001));
+    assert_eq!(hex, h);
+    assert!((dx - 0.49).abs() < 1e-9 && (dy - 0.001).abs() < 1e-9);
+
+    // Just past it, into the northeast neighbor.
+    let ne = hex.neighbor(Direction::NE);
+    let (h, (dx, dy)) = snap::<i32, f64>((cx + 0.51, cy + 0.001));
+    assert_eq!(ne, h);
+    let (nx, ny): (f64, f64) = ne.cartesian_center();
+    assert!((cx + 0.51 - nx - dx).abs() < 1e-9);
+    assert!((cy + 0.001 - ny - dy).abs() < 1e-9);
+}
+
+/// Converts a moving point to a hex, as `snap()` does, but
+/// with hysteresis: the current hex changes only once the
+/// point is more than `margin` past the boundary of the
+/// current hex, so that a point wobbling near an edge does
+/// not flicker between hexes. Distances are in the
+/// unit-width Cartesian coordinates of
+/// `HexCoord::cartesian_center()`.
+#[derive(Debug, Clone)]
+pub struct HexSnapper<T, U> {
+    current: Option<HexCoord<T>>,
+    margin: U,
+}
+
+impl<T, U> HexSnapper<T, U>
+where
+    T: Num + NumCast + Clone + Into<U>,
+    U: Float,
+{
+    /// Make a snapper with no current hex.
+    pub fn new(margin: U) -> Self {
+        HexSnapper {
+            current: None,
+            margin,
+        }
+    }
+
+    /// The current hex, if `update()` has been called.
+    pub fn current(&self) -> Option<HexCoord<T>> {
+        self.current.clone()
+    }
+
+    /// The current hex after moving to `point`. The first
+    /// update just snaps `point`. After that, the hex
+    /// containing `point` replaces the current hex only if
+    /// `point` is more than `margin` beyond the perpendicular
+    /// bisector of their centers, which for neighboring
+    /// hexes is the line of their shared edge.
+    pub fn update(&mut self, point: (U, U)) -> HexCoord<T> {
+        let (hex, _) = snap::<T, U>(point);
+        let switch = match self.current {
+            None => true,
+            Some(ref cur) if *cur == hex => false,
+            Some(ref cur) => {
+                let (cx, cy) = cur.clone().cartesian_center();
+                let (nx, ny) = hex.clone().cartesian_center();
+                let d2 = |x: U, y: U| {
+                    (point.0 - x).powi(2) + (point.1 - y).powi(2)
+                };
+                let sep = (nx - cx).hypot(ny - cy);
+                let past = (d2(cx, cy) - d2(nx, ny)) / (sep + sep);
+                past > self.margin
+            }
+        };
+        if switch {
+            self.current = Some(hex);
+        }
+        self.current.clone().unwrap()
+    }
+}
+
+#[test]
+fn test_hex_snapper() {
+    let a = HexCoord::new(0i32, 0i32);
+    let b = HexCoord::new(1i32, 0i32);
+    let (ax, ay): (f64, f64) = a.cartesian_center();
+    let (bx, by): (f64, f64) = b.cartesian_center();
+    let (mx, my) = ((ax + bx) / 2.0, (ay + by) / 2.0);
+    let (ux, uy) = ((bx - ax) / 2.0, (by - ay) / 2.0);
+    let at = |t: f64| (mx + t * ux, my + t * uy);
+
+    let mut snapper = HexSnapper::new(0.1);
+    assert_eq!(None, snapper.current());
+    assert_eq!(a, snapper.update(at(-0.5)));
+    // Wobbling across the shared edge within the margin.
+    for &t in &[0.05, -0.05, 0.1, -0.02, 0.15, 0.0] {
+        assert_eq!(a, snapper.update(at(t)));
+    }
+    assert_eq!(b, snapper.update(at(0.3)));
+    for &t in &[0.05, -0.05, -0.1, 0.02] {
+        assert_eq!(b, snapper.update(at(t)));
+    }
+    assert_eq!(a, snapper.update(at(-0.3)));
+    assert_eq!(Some(a), snapper.current());
+
+    let mut eager = HexSnapper::new(0.0);
+    eager.update(at(-0.5));
+    assert_eq!(b, eager.update(at(0.01)));
+}
+
+/// True if the points `a` and `b` are within Euclidean
+/// distance `eps` of each other.
+pub fn points_approx_eq<U: Float>(
+    a: (U, U),
+    b: (U, U),
+    eps: U,
+) -> bool {
+    (a.0 - b.0).hypot(a.1 - b.1) <= eps
+}
+
+#[test]
+fn test_points_approx_eq() {
+    assert!(points_approx_eq((0.0, 0.0), (0.3, 0.4), 0.5));
+    assert!(!points_approx_eq((0.0, 0.0), (0.3, 0.4), 0.4999));
+    assert!(points_approx_eq((1.0, 1.0), (1.0, 1.0), 0.0));
+}
+
+impl<T: Num> HexCoord<T> {
+    /// True if the centers of `self` and `other` are within
+    /// Euclidean distance `eps` of each other, in the
+    /// coordinate system of `cartesian_center()`. Mostly
+    /// useful for float coordinates.
+    pub fn approx_eq<U: Float>(self, other: Self, eps: U) -> bool
+    where
+        T: Into<U>,
+    {
+        let a = self.cartesian_center();
+        let b = other.cartesian_center();
+        points_approx_eq(a, b, eps)
+    }
+}
+
+#[test]
+fn test_hex_approx_eq() {
+    let a = HexCoord::new(1.0f64, 2.0f64);
+    let b = HexCoord::new(1.0f64, 2.1f64);
+    let d = 0.1 * 3.0f64.sqrt() / 2.0;
+    assert!(a.approx_eq(b, d * (1.0 + 1e-9)));
+    assert!(!a.approx_eq(b, d * (1.0 - 1e-9)));
+    assert!(a.approx_eq(a, 0.0));
+
+    let c = HexCoord::new(1.02f64, 2.0f64);
+    let (dx, dy) = (0.75 * 0.02, -3.0f64.sqrt() / 2.0 * 0.01);
+    let d = dx.hypot(dy);
+    assert!(a.approx_eq(c, d * (1.0 + 1e-9)));
+    assert!(!a.approx_eq(c, d * (1.0 - 1e-9)));
+}
+
+// Corner `k` of `hex`, as an exact lattice point: the
+// Cartesian corner scaled by `4` in `x` and `4 / sqrt(3)`
+// in `y`. Corners shared between hexes get equal keys.
+fn corner_key<T: PrimInt>(hex: HexCoord<T>, k: usize) -> (T, T) {
+    const OFFSETS: [(i8, i8); 6] =
+        [(2, 0), (1, 1), (-1, 1), (-2, 0), (-1, -1), (1, -1)];
+    let c = |n: i8| -> T { num::cast(n).unwrap() };
+    let (dx, dy) = OFFSETS[k];
+    let x = c(3) * hex.q + c(dx);
+    let y = c(2) * hex.r - hex.q + c(dy);
+    (x, y)
+}
+
+/// Closed outlines of `region`, as loops of Cartesian corner
+/// points in the coordinate system of `cartesian_corners()`.
+/// Each loop lists its corners once, without repeating the
+/// first point at the end. There is one loop for the outer
+/// boundary of each connected piece of the region, traced
+/// counterclockwise, and one for each hole, traced
+/// clockwise. The result is empty for an empty region.
+pub fn outline<T, U>(region: &HexSet<T>) -> Vec<Vec<(U, U)>>
+where
+    T: PrimInt + Hash + Into<U>,
+    U: Float,
+{
+    // Each boundary edge runs counterclockwise around its
+    // region hex, from corner `k` to corner `k + 1`, so the
+    // region is always on the left. Every grid vertex joins
+    // just three edges, so each boundary corner starts
+    // exactly one boundary edge.
+    let mut edges = BTreeMap::new();
+    for &hex in region.iter() {
+        let corners = hex.cartesian_corners();
+        for (k, &d) in DIRECTIONS.iter().enumerate() {
+            if !region.contains(hex.neighbor(d)) {
+                let start = corner_key(hex, k);
+                let end = corner_key(hex, (k + 1) % 6);
+                edges.insert(start, (end, corners[k]));
+            }
+        }
+    }
+
+    let mut loops = Vec::new();
+    while let Some((&first, _)) = edges.iter().next() {
+        let mut points = Vec::new();
+        let mut key = first;
+        while let Some((next, point)) = edges.remove(&key) {
+            points.push(point);
+            key = next;
+        }
+        loops.push(points);
+    }
+    loops
+}
+
+#[test]
+fn test_outline() {
+    let center = HexCoord::new(0i32, 0i32);
+
+    let single: HexSet<i32> = std::iter::once(center).collect();
+    let loops = outline::<i32, f64>(&single);
+    assert_eq!(1, loops.len());
+    assert_eq!(6, loops[0].len());
+    for corner in center.cartesian_corners::<f64>().iter() {
+        assert!(loops[0].contains(corner));
+    }
+
+    let pair: HexSet<i32> = vec![center, center.neighbor(Direction::N)]
+        .into_iter()
+        .collect();
+    let loops = outline::<i32, f64>(&pair);
+    assert_eq!(1, loops.len());
+    assert_eq!(10, loops[0].len());
+
+    let mut ring: HexSet<i32> = shapes::range(center, 1).collect();
+    ring.remove(center);
+    let mut loops = outline::<i32, f64>(&ring);
+    loops.sort_by_key(|l| l.len());
+    assert_eq!(
+        vec![6, 18],
+        loops.iter().map(|l| l.len()).collect::<Vec<_>>()
+    );
+
+    assert!(outline::<i32, f64>(&HexSet::new()).is_empty());
+}
@@ -0,0 +1,176 @@
+//! Chunked storage: the plane partitioned into hexagonal
+//! chunks, each the hexes within a fixed radius of a chunk
+//! center.
+//!
+//! Chunk centers form a coarser hex lattice of their own, so
+//! chunks are identified by a `HexCoord` in which neighboring
+//! chunks are neighboring coordinates. Within a chunk, hexes
+//! are numbered in spiral order: the center is `0`, followed
+//! by each ring in turn in the order of `shapes::ring()`.
+
+use num::{PrimInt, Signed};
+
+use crate::*;
+
+// Center hex of the chunk at lattice position `(i, j)`: the
+// sum `i * u + j * v` of the lattice basis vectors, which are
+// the centers `u` and `v` of the chunks NE and N of the
+// origin chunk.
+fn lattice_center<T: PrimInt + Signed>(
+    i: T,
+    j: T,
+    n: T,
+) -> HexCoord<T> {
+    let (one, two) = (T::one(), T::one() + T::one());
+    let q = i * (two * n + one) + j * n;
+    let r = i * (n + one) + j * (two * n + one);
+    HexCoord::new(q, r)
+}
+
+/// Center hex of `chunk`, for chunks of radius
+/// `chunk_radius`.
+pub fn chunk_center<T>(
+    chunk: HexCoord<T>,
+    chunk_radius: T,
+) -> HexCoord<T>
+where
+    T: PrimInt + Signed,
+{
+    lattice_center(chunk.q, chunk.r - chunk.q, chunk_radius)
+}
+
+/// Number of hexes in a chunk of radius `chunk_radius`.
+pub fn chunk_len(chunk_radius: usize) -> usize {
+    shapes::disk_area(chunk_radius)
+}
+
+/// The chunk containing `hex` for chunks of radius
+/// `chunk_radius`, together with the spiral index of `hex`
+/// within that chunk.
+///
+/// # Panics
+///
+/// Panics if `chunk_radius` is negative.
+pub fn chunk_of<T>(
+    hex: HexCoord<T>,
+    chunk_radius: T,
+) -> (HexCoord<T>, usize)
+where
+    T: PrimInt + Signed,
+{
+    assert!(!chunk_radius.is_negative(), "negative chunk radius");
+    let n = chunk_radius;
+    let (one, two, three) =
+        (T::one(), T::one() + T::one(), T::from(3).unwrap());
+    // Solve `hex = a * u + b * v` in the lattice basis, then
+    // find the nearby lattice point whose chunk holds `hex`.
+    let det = three * n * (n + one) + one;
+    let a = div_floor((two * n + one) * hex.q - n * hex.r, det);
+    let b = div_floor((two * n + one) * hex.r - (n + one) * hex.q, det);
+    for i in num::range_inclusive(a - one, a + two) {
+        for j in num::range_inclusive(b - one, b + two) {
+            let center = lattice_center(i, j, n);
+            if center.distance(hex) <= n {
+                let chunk = HexCoord::new(i, i + j);
+                return (chunk, spiral_index(hex, center));
+            }
+        }
+    }
+    unreachable!("hex not in any chunk")
+}
+
+// Spiral index of `hex` in the chunk centered at `center`.
+fn spiral_index<T>(hex: HexCoord<T>, center: HexCoord<T>) -> usize
+where
+    T: PrimInt + Signed,
+{
+    let k = center.distance(hex);
+    if k.is_zero() {
+        return 0;
+    }
+    let offset = HexCoord::new(hex.q - center.q, hex.r - center.r);
+    let origin = HexCoord::new(T::zero(), T::zero());
+    let pos =
+        shapes::ring(origin, k).position(|h| h == offset).unwrap();
+    chunk_len(k.to_usize().unwrap() - 1) + pos
+}
+
+/// The hex with spiral index `local` in `chunk`, for chunks
+/// of radius `chunk_radius`: the inverse of `chunk_of()`.
+///
+/// # Panics
+///
+/// Panics if `local` is not less than
+/// `chunk_len(chunk_radius)`.
+pub fn from_chunk<T>(
+    chunk: HexCoord<T>,
+    local: usize,
+    chunk_radius: T,
+) -> HexCoord<T>
+where
+    T: PrimInt + Signed,
+{
+    let n = chunk_radius.to_usize().expect("negative chunk radius");
+    assert!(local < chunk_len(n), "chunk index out of range");
+    let center = chunk_center(chunk, chunk_radius);
+    if local == 0 {
+        return center;
+    }
+    let mut k = 1;
+    while chunk_len(k) <= local {
+        k += 1;
+    }
+    let pos = local - chunk_len(k - 1);
+    let offset = shapes::ring(
+        HexCoord::new(T::zero(), T::zero()),
+        T::from(k).unwrap(),
+    )
+    .nth(pos)
+    .unwrap();
+    HexCoord::new(center.q + offset.q, center.r + offset.r)
+}
+
+#[test]
+fn test_chunk_round_trip() {
+    let origin = HexCoord::new(0i32, 0i32);
+    for radius in 0..4 {
+        for hex in shapes::range(origin, 12) {
+            let (chunk, local) = chunk_of(hex, radius);
+            assert!(local < chunk_len(radius as usize));
+            assert_eq!(hex, from_chunk(chunk, local, radius));
+            let center = chunk_center(chunk, radius);
+            assert!(center.distance(hex) <= radius);
+        }
+        for local in 0..chunk_len(radius as usize) {
+            let hex = from_chunk(HexCoord::new(-2, 3), local, radius);
+            assert_eq!(
+                (HexCoord::new(-2, 3), local),
+                chunk_of(hex, radius)
+            );
+        }
+    }
+    assert_eq!((origin, 0), chunk_of(origin, 2));
+    assert_eq!(
+        (HexCoord::new(1, 1), 0),
+        chunk_of(HexCoord::new(5, 3), 2)
+    );
+}
+
+#[test]
+fn test_chunk_tiling() {
+    let origin = HexCoord::new(0i32, 0i32);
+    for radius in 0..4 {
+        let mut seen = std::collections::HashSet::new();
+        for hex in shapes::range(origin, 10) {
+            let (chunk, local) = chunk_of(hex, radius);
+            for n in hex.neighbors() {
+                let (other, _) = chunk_of(n, radius);
+                assert!(chunk.distance(other) <= 1);
+            }
+            if chunk == origin {
+                assert!(seen.insert(local));
+            }
+        }
+        assert_eq!(chunk_len(radius as usize), seen.len());
+    }
+}
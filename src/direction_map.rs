@@ -0,0 +1,81 @@
+use std::ops::{Index, IndexMut};
+
+use crate::*;
+
+/// One value per direction, indexed by `Direction`: per-edge
+/// state of a hex such as wall flags, say.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DirectionMap<V>([V; 6]);
+
+impl<V> DirectionMap<V> {
+    /// Make a map holding `values[d as usize]` for each
+    /// direction `d`, in the order of `Direction::all()`.
+    pub fn new(values: [V; 6]) -> Self {
+        DirectionMap(values)
+    }
+
+    /// Iterator over the directions and their values, in
+    /// the order of `Direction::all()`.
+    pub fn iter(&self) -> impl Iterator<Item = (Direction, &V)> {
+        DIRECTIONS.iter().copied().zip(self.0.iter())
+    }
+
+    /// Iterator over the directions and mutable references
+    /// to their values, in the order of `Direction::all()`.
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (Direction, &mut V)> {
+        DIRECTIONS.iter().copied().zip(self.0.iter_mut())
+    }
+}
+
+impl<V> From<[V; 6]> for DirectionMap<V> {
+    fn from(values: [V; 6]) -> Self {
+        DirectionMap::new(values)
+    }
+}
+
+impl<V> From<DirectionMap<V>> for [V; 6] {
+    fn from(map: DirectionMap<V>) -> Self {
+        map.0
+    }
+}
+
+impl<V> Index<Direction> for DirectionMap<V> {
+    type Output = V;
+    fn index(&self, d: Direction) -> &V {
+        &self.0[d as usize]
+    }
+}
+
+impl<V> IndexMut<Direction> for DirectionMap<V> {
+    fn index_mut(&mut self, d: Direction) -> &mut V {
+        &mut self.0[d as usize]
+    }
+}
+
+#[test]
+fn test_direction_map() {
+    use Direction::*;
+    let mut walls = DirectionMap::new([false; 6]);
+    assert!(!walls[N]);
+    walls[N] = true;
+    walls[SW] = true;
+    assert!(walls[N] && walls[SW] && !walls[S]);
+    let up: Vec<_> =
+        walls.iter().filter(|&(_, &w)| w).map(|(d, _)| d).collect();
+    assert_eq!(vec![N, SW], up);
+
+    let names = DirectionMap::from(["ne", "n", "nw", "sw", "s", "se"]);
+    let order: Vec<_> = names.iter().map(|(d, _)| d).collect();
+    assert_eq!(Direction::all().to_vec(), order);
+    for d in Direction::all() {
+        assert_eq!(format!("{:?}", d).to_lowercase(), names[d]);
+    }
+
+    let mut counts: DirectionMap<u32> = DirectionMap::default();
+    for (d, c) in counts.iter_mut() {
+        *c = d as u32 * 10;
+    }
+    assert_eq!([0, 10, 20, 30, 40, 50], <[u32; 6]>::from(counts));
+}
@@ -0,0 +1,208 @@
+use crate::*;
+
+/// Tie-breaking rule for `FractionalCube::round_with()`:
+/// which component is recomputed when several moved equally
+/// far in rounding.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoundMode {
+    /// The rule of `FractionalCube::round()`: `z` is
+    /// recomputed in preference to `y`, and `y` in
+    /// preference to `x`.
+    #[default]
+    Standard,
+    /// `x` is recomputed in preference to `y`, and `y` in
+    /// preference to `z`.
+    PreferX,
+}
+
+/// A point on the plane of the cube coordinate system, as
+/// opposed to a `HexCubeCoord` lattice point: the result of
+/// interpolating between hexes, say. The components should
+/// sum to (nearly) zero, but this is not checked.
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+pub struct FractionalCube<U> {
+    pub x: U,
+    pub y: U,
+    pub z: U,
+}
+
+impl<U: Float> FractionalCube<U> {
+    /// Make a fractional cube coordinate.
+    pub fn new(x: U, y: U, z: U) -> Self {
+        FractionalCube { x, y, z }
+    }
+
+    /// Return the cube coordinates.
+    pub fn coords(self) -> (U, U, U) {
+        (self.x, self.y, self.z)
+    }
+
+    /// Linear interpolation between `self` (at `t == 0`)
+    /// and `b` (at `t == 1`).
+    pub fn lerp(self, b: Self, t: U) -> Self {
+        let lerp1 = |a: U, b: U| a + (b - a) * t;
+        FractionalCube::new(
+            lerp1(self.x, b.x),
+            lerp1(self.y, b.y),
+            lerp1(self.z, b.z),
+        )
+    }
+
+    /// "Manhattan distance" from `self` to `b`, in hex
+    /// widths: the largest component difference.
+    pub fn distance(self, b: Self) -> U {
+        let dx = (self.x - b.x).abs();
+        let dy = (self.y - b.y).abs();
+        let dz = (self.z - b.z).abs();
+        dx.max(dy).max(dz)
+    }
+
+    /// Cube coordinate of the hex containing `self`. Each
+    /// component is rounded, and the one that moved
+    /// furthest is then recomputed from the other two to
+    /// restore the invariant.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a rounded component is not representable
+    /// in `T`.
+    pub fn round<T: Num + NumCast>(self) -> HexCubeCoord<T> {
+        self.round_with(RoundMode::Standard)
+    }
+
+    /// As `round()`, but with ties between components that
+    /// moved equally far broken by `mode`. Points exactly on
+    /// a hex boundary can round to different hexes under
+    /// different modes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a rounded component is not representable
+    /// in `T`.
+    pub fn round_with<T: Num + NumCast>(
+        self,
+        mode: RoundMode,
+    ) -> HexCubeCoord<T> {
+        let mut x = self.x.round();
+        let mut y = self.y.round();
+        let mut z = self.z.round();
+        let dx = (x - self.x).abs();
+        let dy = (y - self.y).abs();
+        let dz = (z - self.z).abs();
+        let (reset_x, reset_y) = match mode {
+            RoundMode::Standard => (dx > dy && dx > dz, dy > dz),
+            RoundMode::PreferX => (dx >= dy && dx >= dz, dy >= dz),
+        };
+        if reset_x {
+            x = -y - z;
+        } else if reset_y {
+            y = -x - z;
+        } else {
+            z = -x - y;
+        }
+        let cast = |c: U| -> T {
+            num::cast(c).expect("rounded coordinate out of range")
+        };
+        HexCubeCoord::new_unchecked(cast(x), cast(y), cast(z))
+    }
+}
+
+impl<T: Num + Into<U>, U: Float> From<HexCubeCoord<T>>
+    for FractionalCube<U>
+{
+    fn from(c: HexCubeCoord<T>) -> Self {
+        let (x, y, z) = c.coords();
+        FractionalCube::new(x.into(), y.into(), z.into())
+    }
+}
+
+/// Orthogonal projection of the point `(x, y, z)` onto the
+/// cube plane `x + y + z == 0`: the nearest point on the
+/// plane, found by subtracting a third of the sum from each
+/// component. Use this to correct accumulated floating-point
+/// drift before rounding to a hex.
+pub fn project_to_plane<U: Float>(x: U, y: U, z: U) -> (U, U, U) {
+    let third = (x + y + z) / nc!("3", U);
+    (x - third, y - third, z - third)
+}
+
+#[test]
+fn test_project_to_plane() {
+    let (x, y, z) = project_to_plane(1.01f64, 2.0, -3.0);
+    assert!((x + y + z).abs() < 1e-12);
+    assert!((x - 1.0066666666666666).abs() < 1e-12);
+    assert!((y - 1.9966666666666666).abs() < 1e-12);
+
+    let (x, y, z) = project_to_plane(0.5f64, -2.25, 1.75);
+    assert!((x - 0.5).abs() < 1e-12);
+    assert!((y + 2.25).abs() < 1e-12);
+    assert!((z - 1.75).abs() < 1e-12);
+}
+
+#[test]
+fn test_fractional_lerp() {
+    let a = FractionalCube::new(0.0f64, 0.0, 0.0);
+    let b: FractionalCube<f64> =
+        HexCubeCoord::from(HexCoord::new(3i32, 0i32)).into();
+    assert_eq!(a, a.lerp(b, 0.0));
+    assert_eq!(b, a.lerp(b, 1.0));
+    let m = a.lerp(b, 1.0 / 3.0);
+    assert!((m.distance(a) - 1.0).abs() < 1e-12);
+    assert!((m.distance(b) - 2.0).abs() < 1e-12);
+    let (x, y, z) = m.coords();
+    assert!((x + y + z).abs() < 1e-12);
+}
+
+#[test]
+fn test_fractional_round() {
+    let c = FractionalCube::new(0.9f64, -0.4, -0.5);
+    let r: HexCubeCoord<i32> = c.round();
+    assert_eq!((1, 0, -1), r.coords());
+
+    let hex = HexCoord::new(-4i32, 7i32);
+    let exact: FractionalCube<f64> =
+        HexCubeCoord::from(hex).cast::<i32>().into();
+    let nudged = FractionalCube::new(
+        exact.x + 0.3,
+        exact.y - 0.1,
+        exact.z - 0.2,
+    );
+    let r: HexCubeCoord<i32> = nudged.round();
+    assert_eq!(hex, r.into());
+
+    let a = FractionalCube::from(HexCubeCoord::from(HexCoord::new(
+        0i32, 0,
+    )));
+    let b = FractionalCube::from(HexCubeCoord::from(HexCoord::new(
+        4i32, 4,
+    )));
+    let r: HexCubeCoord<i32> = a.lerp(b, 0.5).round();
+    assert_eq!(HexCoord::new(2, 2), r.into());
+    assert_eq!(4.0f64, a.distance(b));
+}
+
+#[test]
+fn test_round_with() {
+    let round = |c: FractionalCube<f64>, mode| {
+        c.round_with::<i32>(mode).coords()
+    };
+    // `x` and `y` tie.
+    let xy = FractionalCube::new(0.4, 0.4, -0.8);
+    assert_eq!((0, 1, -1), round(xy, RoundMode::Standard));
+    assert_eq!((1, 0, -1), round(xy, RoundMode::PreferX));
+    assert_eq!(xy.round::<i32>(), xy.round_with(RoundMode::Standard));
+    // `y` and `z` tie.
+    let yz = FractionalCube::new(-0.8, 0.4, 0.4);
+    assert_eq!((-1, 0, 1), round(yz, RoundMode::Standard));
+    assert_eq!((-1, 1, 0), round(yz, RoundMode::PreferX));
+    // `x` and `z` tie.
+    let xz = FractionalCube::new(0.4, -0.8, 0.4);
+    assert_eq!((0, -1, 1), round(xz, RoundMode::Standard));
+    assert_eq!((1, -1, 0), round(xz, RoundMode::PreferX));
+    // Without a tie, the modes agree.
+    let c = FractionalCube::new(0.9, -0.4, -0.5);
+    assert_eq!(
+        round(c, RoundMode::Standard),
+        round(c, RoundMode::PreferX)
+    );
+}
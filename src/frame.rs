@@ -0,0 +1,86 @@
+use num::{PrimInt, Signed};
+
+use crate::hexset::rotate_about;
+use crate::*;
+
+/// A rotated view of the grid, for maps whose "north" is not
+/// this crate's `Direction::N`. In a frame of `steps` sixths
+/// of a turn, the map's directions are the crate's directions
+/// turned `steps` sixths counterclockwise, and the frame's
+/// Cartesian coordinates are turned back so that the map's
+/// north points up. The default frame is the identity.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Frame {
+    steps: u8,
+}
+
+impl Frame {
+    /// Make a frame rotated `steps` sixths of a turn
+    /// counterclockwise; `steps` is taken modulo 6.
+    pub fn new(steps: u8) -> Self {
+        Frame { steps: steps % 6 }
+    }
+
+    /// Rotation of the frame, in sixths of a turn
+    /// counterclockwise, in `0..6`.
+    pub fn steps(self) -> u8 {
+        self.steps
+    }
+
+    /// The grid direction named by the map direction `d`.
+    pub fn direction(self, d: Direction) -> Direction {
+        d.rotate(-(self.steps as i8))
+    }
+
+    /// Neighbor of `hex` in the map direction `d`.
+    pub fn neighbor<T: Num>(
+        self,
+        hex: HexCoord<T>,
+        d: Direction,
+    ) -> HexCoord<T> {
+        hex.neighbor(self.direction(d))
+    }
+
+    /// Cartesian coordinates of the center of `hex` in the
+    /// frame: those of `HexCoord::cartesian_center()`, turned
+    /// `steps` sixths clockwise about the origin.
+    pub fn cartesian_center<T, U>(self, hex: HexCoord<T>) -> (U, U)
+    where
+        T: PrimInt + Signed + Into<U>,
+        U: Float,
+    {
+        let origin = HexCoord::new(T::zero(), T::zero());
+        let back = (6 - self.steps as usize) % 6;
+        rotate_about(hex, origin, back).cartesian_center()
+    }
+}
+
+#[test]
+fn test_frame() {
+    let hex = HexCoord::new(2i32, -3i32);
+    let identity = Frame::default();
+    assert_eq!(Frame::new(6), identity);
+    for &d in &DIRECTIONS {
+        assert_eq!(d, identity.direction(d));
+        assert_eq!(hex.neighbor(d), identity.neighbor(hex, d));
+    }
+    let center: (f64, f64) = identity.cartesian_center(hex);
+    assert_eq!(hex.cartesian_center::<f64>(), center);
+
+    // In every frame, stepping in a map direction moves the
+    // frame's Cartesian position just as stepping in that
+    // grid direction does in the unrotated grid.
+    let origin = HexCoord::new(0i32, 0i32);
+    for steps in 0..6 {
+        let frame = Frame::new(steps);
+        let p: (f64, f64) = frame.cartesian_center(hex);
+        for &d in &DIRECTIONS {
+            let n: (f64, f64) =
+                frame.cartesian_center(frame.neighbor(hex, d));
+            let want = origin.neighbor(d).cartesian_center::<f64>();
+            assert!((n.0 - p.0 - want.0).abs() < 1e-12);
+            assert!((n.1 - p.1 - want.1).abs() < 1e-12);
+        }
+    }
+    assert_eq!(Direction::NW, Frame::new(1).direction(Direction::N));
+}
@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use num::{PrimInt, Signed};
+
+use crate::*;
+
+/// Values keyed by hex location: a thin wrapper around a
+/// `HashMap` with some grid-aware helpers.
+#[derive(Debug, Clone)]
+pub struct HexMap<T, V> {
+    map: HashMap<HexCoord<T>, V>,
+}
+
+impl<T, V> Default for HexMap<T, V> {
+    fn default() -> Self {
+        HexMap {
+            map: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Hash + Eq, V> HexMap<T, V> {
+    /// Make an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of occupied hexes.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// True if no hex is occupied.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Value at `hex`, if any.
+    pub fn get(&self, hex: HexCoord<T>) -> Option<&V> {
+        self.map.get(&hex)
+    }
+
+    /// Mutable value at `hex`, if any.
+    pub fn get_mut(&mut self, hex: HexCoord<T>) -> Option<&mut V> {
+        self.map.get_mut(&hex)
+    }
+
+    /// Store `value` at `hex`, returning the value that was
+    /// previously there, if any.
+    pub fn insert(&mut self, hex: HexCoord<T>, value: V) -> Option<V> {
+        self.map.insert(hex, value)
+    }
+
+    /// Remove and return the value at `hex`, if any.
+    pub fn remove(&mut self, hex: HexCoord<T>) -> Option<V> {
+        self.map.remove(&hex)
+    }
+
+    /// True if `hex` has a value.
+    pub fn contains(&self, hex: HexCoord<T>) -> bool {
+        self.map.contains_key(&hex)
+    }
+
+    /// Iterator over occupied hexes and their values, in
+    /// arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&HexCoord<T>, &V)> {
+        self.map.iter()
+    }
+
+    /// Iterator over the occupied neighbors of `hex`, as
+    /// the direction of the neighbor paired with its value.
+    /// Directions are visited counterclockwise starting
+    /// with `Direction::NE`.
+    pub fn neighbors_of(
+        &self,
+        hex: HexCoord<T>,
+    ) -> impl Iterator<Item = (Direction, &V)>
+    where
+        T: Num + Copy,
+    {
+        DIRECTIONS.iter().filter_map(move |&d| {
+            self.get(hex.neighbor(d)).map(|v| (d, v))
+        })
+    }
+
+    /// Hex nearest the weighted average position of the
+    /// occupied hexes, each weighted by `weight` of its
+    /// value. `None` if the map is empty or the total weight
+    /// is zero.
+    pub fn weighted_centroid<F>(&self, weight: F) -> Option<HexCoord<T>>
+    where
+        T: Num + Copy + ToPrimitive + NumCast,
+        F: Fn(&V) -> f64,
+    {
+        let (mut x, mut y, mut z, mut total) = (0.0, 0.0, 0.0, 0.0);
+        for (&hex, v) in self.iter() {
+            let w = weight(v);
+            let f = |c: T| {
+                c.to_f64().expect("coordinate not representable")
+            };
+            let (q, r) = (f(hex.q), f(hex.r));
+            x += w * q;
+            y += w * (r - q);
+            z -= w * r;
+            total += w;
+        }
+        if total == 0.0 {
+            return None;
+        }
+        let c = FractionalCube::new(x / total, y / total, z / total);
+        Some(c.round::<T>().into())
+    }
+}
+
+impl<T: PrimInt + Signed + Hash, V> HexMap<T, V> {
+    /// The map as a dense grid of offset coordinates, with
+    /// the offset coordinate of the grid's first cell: for
+    /// origin `o`, `grid[row][col]` holds the value at
+    /// `OffsetCoord::new(o.col + col, o.row + row)`. The
+    /// grid is just large enough to hold every occupied hex,
+    /// and unoccupied cells are `None`. An empty map gives an
+    /// empty grid with origin `(0, 0)`.
+    pub fn to_offset_grid(
+        &self,
+        kind: OffsetKind,
+    ) -> (OffsetCoord<T>, Vec<Vec<Option<&V>>>) {
+        let cells: Vec<_> = self
+            .iter()
+            .map(|(&hex, v)| (OffsetCoord::from_hex(hex, kind), v))
+            .collect();
+        let col0 = cells.iter().map(|(off, _)| off.col).min();
+        let row0 = cells.iter().map(|(off, _)| off.row).min();
+        let origin = OffsetCoord::new(
+            col0.unwrap_or_else(T::zero),
+            row0.unwrap_or_else(T::zero),
+        );
+        let index = |c: T, c0: T| {
+            (c - c0).to_usize().expect("map too large for a grid")
+        };
+        let cells: Vec<_> = cells
+            .into_iter()
+            .map(|(off, v)| {
+                (
+                    index(off.col, origin.col),
+                    index(off.row, origin.row),
+                    v,
+                )
+            })
+            .collect();
+        let cols = cells.iter().map(|&(c, _, _)| c + 1).max();
+        let rows = cells.iter().map(|&(_, r, _)| r + 1).max();
+        let mut grid =
+            vec![vec![None; cols.unwrap_or(0)]; rows.unwrap_or(0)];
+        for (col, row, v) in cells {
+            grid[row][col] = Some(v);
+        }
+        (origin, grid)
+    }
+
+    /// Map holding the `Some` cells of `grid`, where
+    /// `grid[row][col]` is the value at
+    /// `OffsetCoord::new(origin.col + col, origin.row + row)`.
+    /// This is the inverse of `to_offset_grid()`. Rows need
+    /// not have equal length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset coordinate of a cell is not
+    /// representable in `T`.
+    pub fn from_offset_grid(
+        origin: OffsetCoord<T>,
+        grid: Vec<Vec<Option<V>>>,
+        kind: OffsetKind,
+    ) -> Self {
+        let coord = |i: usize, c0: T| {
+            T::from(i)
+                .and_then(|i| c0.checked_add(&i))
+                .expect("grid index out of range")
+        };
+        let mut map = HexMap::new();
+        for (row, cells) in grid.into_iter().enumerate() {
+            for (col, cell) in cells.into_iter().enumerate() {
+                if let Some(v) = cell {
+                    let off = OffsetCoord::new(
+                        coord(col, origin.col),
+                        coord(row, origin.row),
+                    );
+                    map.insert(off.to_hex(kind), v);
+                }
+            }
+        }
+        map
+    }
+}
+
+#[test]
+fn test_hexmap_insert_get_remove() {
+    let mut map = HexMap::new();
+    let hex = HexCoord::new(1i32, -2i32);
+    assert!(map.is_empty());
+    assert_eq!(None, map.insert(hex, 'a'));
+    assert_eq!(Some('a'), map.insert(hex, 'b'));
+    assert!(map.contains(hex));
+    assert_eq!(Some(&'b'), map.get(hex));
+    *map.get_mut(hex).unwrap() = 'c';
+    assert_eq!(1, map.len());
+    assert_eq!(vec![(&hex, &'c')], map.iter().collect::<Vec<_>>());
+    assert_eq!(Some('c'), map.remove(hex));
+    assert_eq!(None, map.remove(hex));
+    assert!(!map.contains(hex));
+    assert_eq!(None, map.get(hex));
+}
+
+#[test]
+fn test_hexmap_neighbors_of() {
+    use Direction::*;
+    let center = HexCoord::new(0i32, 0i32);
+    let mut map = HexMap::new();
+    map.insert(center, 0);
+    map.insert(center.neighbor(N), 1);
+    map.insert(center.neighbor(SW), 2);
+    map.insert(center.neighbor(N).neighbor(N), 3);
+    let neighbors: Vec<_> = map.neighbors_of(center).collect();
+    assert_eq!(vec![(N, &1), (SW, &2)], neighbors);
+    let neighbors: Vec<_> =
+        map.neighbors_of(center.neighbor(N)).collect();
+    assert_eq!(vec![(N, &3), (S, &0)], neighbors);
+}
+
+#[test]
+fn test_hexmap_weighted_centroid() {
+    let mut map = HexMap::new();
+    assert_eq!(None, map.weighted_centroid(|&w| w));
+    for q in 0..5i32 {
+        map.insert(HexCoord::new(q, 0), 1.0);
+    }
+    assert_eq!(
+        Some(HexCoord::new(2, 0)),
+        map.weighted_centroid(|_| 1.0)
+    );
+    assert_eq!(None, map.weighted_centroid(|_| 0.0));
+
+    *map.get_mut(HexCoord::new(4, 0)).unwrap() = 20.0;
+    let heavy = map.weighted_centroid(|&w| w).unwrap();
+    assert_eq!(HexCoord::new(4, 0), heavy);
+}
+
+#[test]
+fn test_hexmap_offset_grid() {
+    for &kind in &[OffsetKind::OddQ, OffsetKind::EvenQ] {
+        let mut map = HexMap::new();
+        for &(col, row, v) in
+            &[(0i32, 0i32, 'a'), (3, 1, 'b'), (1, 4, 'c')]
+        {
+            map.insert(OffsetCoord::new(col, row).to_hex(kind), v);
+        }
+        let (origin, grid) = map.to_offset_grid(kind);
+        assert_eq!(OffsetCoord::new(0, 0), origin);
+        assert_eq!(5, grid.len());
+        assert!(grid.iter().all(|row| row.len() == 4));
+        assert_eq!(Some(&'b'), grid[1][3]);
+        assert_eq!(None, grid[1][2]);
+        assert_eq!(3, grid.iter().flatten().flatten().count());
+
+        // `(0, 1)` is at offset row -1, and the rest reach
+        // negative columns too.
+        let mut negative = HexMap::new();
+        for &(q, r, v) in &[(0, 1, 'd'), (-3, 2, 'e'), (2, -5, 'f')] {
+            negative.insert(HexCoord::new(q, r), v);
+        }
+        let (origin, grid) = negative.to_offset_grid(kind);
+        assert_eq!(-3, origin.col);
+        assert!(origin.row < 0);
+        let d = OffsetCoord::from_hex(HexCoord::new(0, 1), kind);
+        let (col, row) = (d.col - origin.col, d.row - origin.row);
+        assert_eq!(Some(&'d'), grid[row as usize][col as usize]);
+
+        for map in &[map, negative] {
+            let (origin, grid) = map.to_offset_grid(kind);
+            let owned: Vec<Vec<Option<char>>> = grid
+                .into_iter()
+                .map(|row| {
+                    row.into_iter().map(|v| v.copied()).collect()
+                })
+                .collect();
+            let restored =
+                HexMap::from_offset_grid(origin, owned, kind);
+            assert_eq!(map.len(), restored.len());
+            for (&hex, v) in map.iter() {
+                assert_eq!(Some(v), restored.get(hex));
+            }
+        }
+    }
+    let empty: HexMap<i32, ()> = HexMap::new();
+    let (origin, grid) = empty.to_offset_grid(OffsetKind::OddQ);
+    assert_eq!(OffsetCoord::new(0, 0), origin);
+    assert!(grid.is_empty());
+}
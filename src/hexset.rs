@@ -0,0 +1,219 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::iter::FromIterator;
+
+use num::{PrimInt, Signed};
+
+use crate::*;
+
+/// A set of hex locations: a thin wrapper around a
+/// `HashSet`, used to describe regions of the grid.
+#[derive(Debug, Clone)]
+pub struct HexSet<T> {
+    set: HashSet<HexCoord<T>>,
+}
+
+impl<T> Default for HexSet<T> {
+    fn default() -> Self {
+        HexSet {
+            set: HashSet::new(),
+        }
+    }
+}
+
+impl<T: Hash + Eq> PartialEq for HexSet<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.set == other.set
+    }
+}
+
+impl<T: Hash + Eq> Eq for HexSet<T> {}
+
+impl<T: Hash + Eq> HexSet<T> {
+    /// Make an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of hexes in the set.
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    /// True if the set has no hexes.
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Add `hex` to the set, returning `false` if it was
+    /// already present.
+    pub fn insert(&mut self, hex: HexCoord<T>) -> bool {
+        self.set.insert(hex)
+    }
+
+    /// Remove `hex` from the set, returning `false` if it
+    /// was not present.
+    pub fn remove(&mut self, hex: HexCoord<T>) -> bool {
+        self.set.remove(&hex)
+    }
+
+    /// True if `hex` is in the set.
+    pub fn contains(&self, hex: HexCoord<T>) -> bool {
+        self.set.contains(&hex)
+    }
+
+    /// Iterator over the hexes of the set, in arbitrary
+    /// order.
+    pub fn iter(&self) -> impl Iterator<Item = &HexCoord<T>> {
+        self.set.iter()
+    }
+}
+
+impl<T: Num + Copy + Hash + Eq> HexSet<T> {
+    /// The set grown by one ring: its hexes together with
+    /// all their neighbors.
+    pub fn dilate(&self) -> HexSet<T> {
+        self.iter()
+            .flat_map(|&h| std::iter::once(h).chain(h.neighbors()))
+            .collect()
+    }
+
+    /// The set shrunk by one ring: those of its hexes whose
+    /// neighbors are all in the set.
+    pub fn erode(&self) -> HexSet<T> {
+        self.iter()
+            .copied()
+            .filter(|&h| h.neighbors().all(|n| self.contains(n)))
+            .collect()
+    }
+}
+
+#[test]
+fn test_dilate_erode() {
+    let center = HexCoord::new(4i32, -1i32);
+    let single: HexSet<i32> = std::iter::once(center).collect();
+    let flower = single.dilate();
+    assert_eq!(7, flower.len());
+    assert_eq!(shapes::range(center, 1).collect::<HexSet<_>>(), flower);
+    assert_eq!(single, flower.erode());
+    assert_eq!(
+        shapes::range(center, 3).collect::<HexSet<_>>(),
+        flower.dilate().dilate()
+    );
+    assert!(single.erode().is_empty());
+    assert!(HexSet::<i32>::new().dilate().is_empty());
+}
+
+// `hex` rotated counterclockwise about `center` by `steps`
+// sixths of a turn.
+pub(crate) fn rotate_about<T>(
+    hex: HexCoord<T>,
+    center: HexCoord<T>,
+    steps: usize,
+) -> HexCoord<T>
+where
+    T: PrimInt + Signed,
+{
+    let offset = HexCoord::new(hex.q - center.q, hex.r - center.r);
+    let (mut x, mut y, mut z) = HexCubeCoord::from(offset).coords();
+    for _ in 0..steps % 6 {
+        let (rx, ry, rz) = (-y, -z, -x);
+        x = rx;
+        y = ry;
+        z = rz;
+    }
+    let offset = HexCoord::from(HexCubeCoord::new_unchecked(x, y, z));
+    HexCoord::new(center.q + offset.q, center.r + offset.r)
+}
+
+impl<T: PrimInt + Signed + Hash> HexSet<T> {
+    /// True if the set is unchanged by rotation about
+    /// `center` through `1 / fold` of a full turn, that is
+    /// by `6 / fold` sixth-turns.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `fold` is one of `1`, `2`, `3` or `6`.
+    pub fn has_rotational_symmetry(
+        &self,
+        center: HexCoord<T>,
+        fold: u8,
+    ) -> bool {
+        assert!(
+            fold > 0 && 6 % fold == 0,
+            "rotational symmetry fold must divide 6"
+        );
+        let steps = (6 / fold) as usize;
+        self.iter()
+            .all(|&h| self.contains(rotate_about(h, center, steps)))
+    }
+}
+
+#[test]
+fn test_rotational_symmetry() {
+    use Direction::*;
+    let center = HexCoord::new(1i32, 2i32);
+
+    // A flower with three long petals.
+    let mut flower: HexSet<i32> = shapes::range(center, 1).collect();
+    for &d in &[NE, NW, S] {
+        flower.insert(center.neighbor(d).neighbor(d));
+    }
+    assert!(flower.has_rotational_symmetry(center, 1));
+    assert!(flower.has_rotational_symmetry(center, 3));
+    assert!(!flower.has_rotational_symmetry(center, 2));
+    assert!(!flower.has_rotational_symmetry(center, 6));
+    let off_center = center.neighbor(N);
+    assert!(!flower.has_rotational_symmetry(off_center, 3));
+
+    let disk: HexSet<i32> = shapes::range(center, 2).collect();
+    for &fold in &[1, 2, 3, 6] {
+        assert!(disk.has_rotational_symmetry(center, fold));
+    }
+
+    let blob: HexSet<i32> =
+        vec![center, center.neighbor(N), center.neighbor(NE)]
+            .into_iter()
+            .collect();
+    assert!(!blob.has_rotational_symmetry(center, 2));
+    assert!(!blob.has_rotational_symmetry(center, 3));
+
+    let pair: HexSet<i32> =
+        vec![center.neighbor(N), center.neighbor(S)]
+            .into_iter()
+            .collect();
+    assert!(pair.has_rotational_symmetry(center, 2));
+    assert!(!pair.has_rotational_symmetry(center, 3));
+}
+
+impl<T: Hash + Eq> FromIterator<HexCoord<T>> for HexSet<T> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = HexCoord<T>>,
+    {
+        HexSet {
+            set: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[test]
+fn test_hexset() {
+    let hex = HexCoord::new(3i32, 1i32);
+    let mut set = HexSet::new();
+    assert!(set.is_empty());
+    assert!(set.insert(hex));
+    assert!(!set.insert(hex));
+    assert!(set.contains(hex));
+    assert_eq!(1, set.len());
+    assert_eq!(vec![&hex], set.iter().collect::<Vec<_>>());
+    assert!(set.remove(hex));
+    assert!(!set.remove(hex));
+    assert!(!set.contains(hex));
+
+    let region: HexSet<i32> = shapes::range(hex, 1).collect();
+    assert_eq!(7, region.len());
+    let mut hexes: Vec<_> = shapes::range(hex, 1).collect();
+    hexes.reverse();
+    assert_eq!(region, hexes.into_iter().collect());
+}
@@ -0,0 +1,86 @@
+use crate::*;
+
+/// Hash key for a hex location that does not care which
+/// coordinate system it came from: axial and cube
+/// coordinates of the same hex make equal keys.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct HexKey<T>(HexCoord<T>);
+
+impl<T> HexKey<T> {
+    /// The axial coordinate of the keyed hex.
+    pub fn hex(self) -> HexCoord<T> {
+        self.0
+    }
+}
+
+impl<T> From<HexCoord<T>> for HexKey<T> {
+    fn from(hex: HexCoord<T>) -> Self {
+        HexKey(hex)
+    }
+}
+
+impl<T: Num> From<HexCubeCoord<T>> for HexKey<T> {
+    fn from(cube: HexCubeCoord<T>) -> Self {
+        HexKey(cube.into())
+    }
+}
+
+impl<T> From<HexKey<T>> for HexCoord<T> {
+    fn from(key: HexKey<T>) -> Self {
+        key.0
+    }
+}
+
+/// Canonical key for the edge shared by adjacent hexes `a`
+/// and `b`: the lesser of the two hexes, paired with the
+/// direction from it to the other. The key is the same
+/// whichever order the hexes are given in, so it can be used
+/// to store one value per edge. `None` if the hexes are not
+/// adjacent.
+pub fn canonical_edge<T>(
+    a: HexCoord<T>,
+    b: HexCoord<T>,
+) -> Option<(HexCoord<T>, Direction)>
+where
+    T: Num + Copy + Ord,
+{
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    lo.direction_to(hi).map(|d| (lo, d))
+}
+
+#[test]
+fn test_hex_key() {
+    use std::collections::HashMap;
+
+    let hex = HexCoord::new(-2i32, 5i32);
+    let cube = HexCubeCoord::from(hex);
+    assert_eq!(HexKey::from(hex), HexKey::from(cube));
+
+    let mut map = HashMap::new();
+    map.insert(HexKey::from(cube), "castle");
+    assert_eq!(Some(&"castle"), map.get(&hex.into()));
+    assert_eq!(
+        None,
+        map.get(&HexKey::from(hex.neighbor(Direction::N)))
+    );
+    assert_eq!(hex, HexKey::from(cube).hex());
+}
+
+#[test]
+fn test_canonical_edge() {
+    let hex = HexCoord::new(3i32, -1i32);
+    let mut keys = std::collections::HashSet::new();
+    for n in hex.neighbors() {
+        let key = canonical_edge(hex, n);
+        assert_eq!(key, canonical_edge(n, hex));
+        let (lo, d) = key.unwrap();
+        assert_eq!(hex.min(n), lo);
+        assert_eq!(hex.max(n), lo.neighbor(d));
+        keys.insert(key);
+    }
+    assert_eq!(6, keys.len());
+    assert_eq!(None, canonical_edge(hex, hex));
+    assert_eq!(None, canonical_edge(hex, HexCoord::new(5, -1)));
+}
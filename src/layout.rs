@@ -0,0 +1,541 @@
+use std::hash::Hash;
+
+use crate::*;
+
+/// A hex edge as given by `Layout::edges()`: the direction
+/// of the neighbor across the edge, and the edge's two
+/// endpoints in counterclockwise order.
+pub type Edge<U> = (Direction, (U, U), (U, U));
+
+/// Orientation convention of axial coordinates. This crate
+/// is right-handed, with `r` increasing to the north; in
+/// left-handed data `r` increases to the south, so that the
+/// grid is mirrored north to south. A left-handed `(q, r)`
+/// is the right-handed `(q, q - r)`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Handedness {
+    /// `r` increases to the north, as elsewhere in this
+    /// crate.
+    #[default]
+    Right,
+    /// `r` increases to the south.
+    Left,
+}
+
+impl Handedness {
+    /// The right-handed coordinate of `hex`, given in this
+    /// convention.
+    pub fn to_right<T>(self, hex: HexCoord<T>) -> HexCoord<T>
+    where
+        T: Num + Clone,
+    {
+        match self {
+            Handedness::Right => hex,
+            Handedness::Left => {
+                HexCoord::new(hex.q.clone(), hex.q - hex.r)
+            }
+        }
+    }
+
+    /// The coordinate in this convention of the
+    /// right-handed `hex`.
+    pub fn from_right<T>(self, hex: HexCoord<T>) -> HexCoord<T>
+    where
+        T: Num + Clone,
+    {
+        // The mirroring is its own inverse.
+        self.to_right(hex)
+    }
+
+    /// The direction whose `HexCoord::neighbor()` step, on
+    /// coordinates in this convention, goes the way `d`
+    /// points on the right-handed grid: for left-handed
+    /// coordinates, `Direction::S` moves north.
+    pub fn direction(self, d: Direction) -> Direction {
+        match self {
+            Handedness::Right => d,
+            Handedness::Left => d.reflect(Axis::Q),
+        }
+    }
+}
+
+/// Placement of the hex grid on a pixel plane: the unit-width
+/// Cartesian coordinates of `HexCoord::cartesian_center()`
+/// are scaled componentwise by `size` and then offset by
+/// `origin`. The default layout is the identity.
+///
+/// A negative size component mirrors the grid. In
+/// particular, a negative `y` size keeps north at the top
+/// of a screen whose `y` increases downward. Mirroring also
+/// reverses the winding of `polygon_corners()` in pixel
+/// coordinates.
+///
+/// Hexes given to and returned by a layout are in the
+/// convention of its `handedness`, which is right-handed
+/// unless set with `with_handedness()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout<U> {
+    /// Pixel width and height of a unit hex.
+    pub size: (U, U),
+    /// Pixel position of the center of the origin hex.
+    pub origin: (U, U),
+    /// Convention of the axial coordinates laid out.
+    pub handedness: Handedness,
+}
+
+impl<U: Float> Default for Layout<U> {
+    fn default() -> Self {
+        Layout {
+            size: (U::one(), U::one()),
+            origin: (U::zero(), U::zero()),
+            handedness: Handedness::Right,
+        }
+    }
+}
+
+impl<U: Float> Layout<U> {
+    /// Make a layout with the given hex size and origin.
+    pub fn new(size: (U, U), origin: (U, U)) -> Self {
+        Layout {
+            size,
+            origin,
+            handedness: Handedness::Right,
+        }
+    }
+
+    /// This layout, for coordinates in the given convention.
+    pub fn with_handedness(self, handedness: Handedness) -> Self {
+        Layout { handedness, ..self }
+    }
+
+    fn transform(&self, (x, y): (U, U)) -> (U, U) {
+        (
+            self.origin.0 + self.size.0 * x,
+            self.origin.1 + self.size.1 * y,
+        )
+    }
+
+    fn untransform(&self, (x, y): (U, U)) -> (U, U) {
+        (
+            (x - self.origin.0) / self.size.0,
+            (y - self.origin.1) / self.size.1,
+        )
+    }
+
+    /// Pixel position of the center of `hex`.
+    pub fn hex_to_pixel<T>(&self, hex: HexCoord<T>) -> (U, U)
+    where
+        T: Num + Clone + Into<U>,
+    {
+        self.transform(self.handedness.to_right(hex).cartesian_center())
+    }
+
+    /// Hex containing the pixel position `p`.
+    pub fn pixel_to_hex<T>(&self, p: (U, U)) -> HexCoord<T>
+    where
+        T: Num + Clone + NumCast,
+    {
+        let hex = HexCoord::from_cartesian(self.untransform(p));
+        self.handedness.from_right(hex)
+    }
+
+    /// The indices into `points` of the pixel positions in
+    /// each hex, as found by `pixel_to_hex()`, in increasing
+    /// order. Only hexes containing some point are present.
+    pub fn bucket_points<T>(
+        &self,
+        points: &[(U, U)],
+    ) -> HexMap<T, Vec<usize>>
+    where
+        T: Num + Clone + NumCast + Hash + Eq,
+    {
+        let mut buckets: HexMap<T, Vec<usize>> = HexMap::new();
+        for (i, &p) in points.iter().enumerate() {
+            let hex = self.pixel_to_hex(p);
+            match buckets.get_mut(hex.clone()) {
+                Some(bucket) => bucket.push(i),
+                None => {
+                    buckets.insert(hex, vec![i]);
+                }
+            }
+        }
+        buckets
+    }
+
+    /// Pixel positions of the corners of `hex`, in the
+    /// order of `HexCoord::cartesian_corners()`.
+    pub fn polygon_corners<T>(&self, hex: HexCoord<T>) -> [(U, U); 6]
+    where
+        T: Num + Clone + Into<U>,
+    {
+        self.corners_with(&CornerOffsets::new(), hex)
+    }
+
+    /// `polygon_corners()` of each of `hexes`, in order. The
+    /// corner offsets are computed once for the whole batch
+    /// rather than once per hex, which matters when redrawing
+    /// a large grid.
+    pub fn polygon_corners_iter<'a, T, I>(
+        &'a self,
+        hexes: I,
+    ) -> impl Iterator<Item = [(U, U); 6]> + 'a
+    where
+        T: Num + Clone + Into<U>,
+        I: IntoIterator<Item = HexCoord<T>>,
+        I::IntoIter: 'a,
+    {
+        let offsets = CornerOffsets::new();
+        hexes
+            .into_iter()
+            .map(move |hex| self.corners_with(&offsets, hex))
+    }
+
+    fn corners_with<T>(
+        &self,
+        offsets: &CornerOffsets<U>,
+        hex: HexCoord<T>,
+    ) -> [(U, U); 6]
+    where
+        T: Num + Clone + Into<U>,
+    {
+        let hex = self.handedness.to_right(hex);
+        let mut corners = offsets.corners(hex);
+        for c in corners.iter_mut() {
+            *c = self.transform(*c);
+        }
+        corners
+    }
+
+    /// Pixel-space edges of `hex`, each tagged with the
+    /// direction of the neighbor across it. Edges are given
+    /// in direction order, each from corner `i` to corner
+    /// `i + 1` (mod 6) of `polygon_corners()`, so they run
+    /// counterclockwise around the hex. Directions are in the
+    /// layout's convention, as given by
+    /// `Handedness::direction()`.
+    pub fn edges<T>(&self, hex: HexCoord<T>) -> [Edge<U>; 6]
+    where
+        T: Num + Clone + Into<U>,
+    {
+        let c = self.polygon_corners(hex);
+        let edge = |i: usize| {
+            let d = self.handedness.direction(DIRECTIONS[i]);
+            (d, c[i], c[(i + 1) % 6])
+        };
+        [edge(0), edge(1), edge(2), edge(3), edge(4), edge(5)]
+    }
+
+    /// Iterator over the hexes whose polygons overlap the
+    /// pixel rectangle with opposite corners `min` and
+    /// `max`, for drawing just the visible part of a grid.
+    /// Hexes that only touch the rectangle's boundary are
+    /// excluded. Hexes are given column by column, west to
+    /// east, each column south to north in Cartesian terms.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a hex coordinate in the rectangle is not
+    /// representable in `T`.
+    pub fn hexes_in_rect<T>(
+        &self,
+        min: (U, U),
+        max: (U, U),
+    ) -> impl Iterator<Item = HexCoord<T>>
+    where
+        T: Num + Clone + NumCast,
+    {
+        // Work in unit-hex Cartesian space, where the size
+        // may have flipped the rectangle.
+        let (ax, ay) = self.untransform(min);
+        let (bx, by) = self.untransform(max);
+        let (x0, x1) = (ax.min(bx), ax.max(bx));
+        let (y0, y1) = (ay.min(by), ay.max(by));
+        let h = half!(U);
+        let dx = nc!("0.75", U);
+        let dy = sqrt3d2!(U);
+        let inr = h * dy;
+        let to_i64 = |c: U| c.to_i64().expect("rectangle out of range");
+        let coord = |c: i64| T::from(c).expect("hex out of range");
+
+        // Projections of the rectangle onto the normals of the
+        // two slanted pairs of hex edges.
+        let (nx, ny) = (dy, h);
+        let dot = |(x, y): (U, U), sx: U| sx * nx * x + ny * y;
+        let span = |sx: U| {
+            let p = [
+                dot((x0, y0), sx),
+                dot((x0, y1), sx),
+                dot((x1, y0), sx),
+                dot((x1, y1), sx),
+            ];
+            let lo = p.iter().fold(p[0], |a, &b| a.min(b));
+            let hi = p.iter().fold(p[0], |a, &b| a.max(b));
+            (lo, hi)
+        };
+        let spans =
+            [(U::one(), span(U::one())), (-U::one(), span(-U::one()))];
+
+        let mut hexes = Vec::new();
+        let q0 = to_i64(((x0 - h) / dx).floor());
+        let q1 = to_i64(((x1 + h) / dx).ceil());
+        for q in q0..=q1 {
+            let qf = U::from(q).unwrap();
+            let cx = dx * qf;
+            if cx - h >= x1 || cx + h <= x0 {
+                continue;
+            }
+            let r0 = to_i64(((y0 - inr) / dy + qf * h).floor());
+            let r1 = to_i64(((y1 + inr) / dy + qf * h).ceil());
+            for r in r0..=r1 {
+                let cy = dy * (U::from(r).unwrap() - qf * h);
+                if cy - inr >= y1 || cy + inr <= y0 {
+                    continue;
+                }
+                let overlaps = spans.iter().all(|&(sx, (lo, hi))| {
+                    let c = dot((cx, cy), sx);
+                    c - inr < hi && c + inr > lo
+                });
+                if overlaps {
+                    let hex = HexCoord::new(coord(q), coord(r));
+                    hexes.push(self.handedness.from_right(hex));
+                }
+            }
+        }
+        hexes.into_iter()
+    }
+
+    /// Triangle-fan mesh filling `hex`, for rendering: seven
+    /// pixel-space vertices, the center followed by the corners
+    /// in the order of `polygon_corners()`, and six triangles
+    /// indexing them. Each triangle joins the center to one
+    /// edge, with the same winding as the corners.
+    pub fn triangulate<T>(
+        &self,
+        hex: HexCoord<T>,
+    ) -> (Vec<(U, U)>, Vec<[u16; 3]>)
+    where
+        T: Num + Clone + Into<U>,
+    {
+        let mut vertices = vec![self.hex_to_pixel(hex.clone())];
+        vertices.extend_from_slice(&self.polygon_corners(hex));
+        let triangles =
+            (0..6).map(|i| [0, i + 1, (i + 1) % 6 + 1]).collect();
+        (vertices, triangles)
+    }
+}
+
+#[test]
+fn test_layout() {
+    let hex = HexCoord::new(2i32, -1i32);
+    let unit = Layout::default();
+    assert_eq!(hex.cartesian_center::<f64>(), unit.hex_to_pixel(hex));
+    assert_eq!(
+        hex.cartesian_corners::<f64>(),
+        unit.polygon_corners(hex)
+    );
+
+    let layout = Layout::new((20.0f64, 30.0), (100.0, 50.0));
+    let (x, y) = hex.cartesian_center::<f64>();
+    assert_eq!(
+        (100.0 + 20.0 * x, 50.0 + 30.0 * y),
+        layout.hex_to_pixel(hex)
+    );
+    assert_eq!(hex, layout.pixel_to_hex(layout.hex_to_pixel(hex)));
+    let corner = layout.polygon_corners(hex)[3];
+    let (cx, cy) = hex.cartesian_corners::<f64>()[3];
+    assert_eq!((100.0 + 20.0 * cx, 50.0 + 30.0 * cy), corner);
+}
+
+#[test]
+fn test_polygon_corners_iter() {
+    let layout = Layout::new((20.0f32, -30.0), (100.0, 50.0))
+        .with_handedness(Handedness::Left);
+    let hexes: Vec<HexCoord<i16>> =
+        shapes::range(HexCoord::new(3, -2), 4).collect();
+    let batch: Vec<_> =
+        layout.polygon_corners_iter(hexes.clone()).collect();
+    assert_eq!(hexes.len(), batch.len());
+    for (&hex, corners) in hexes.iter().zip(batch.iter()) {
+        assert_eq!(&layout.polygon_corners(hex), corners);
+    }
+}
+
+#[test]
+fn test_triangulate() {
+    let hex = HexCoord::new(-1i32, 3i32);
+    let layout = Layout::new((20.0f64, 30.0), (100.0, 50.0));
+    let (vertices, triangles) = layout.triangulate(hex);
+    assert_eq!(7, vertices.len());
+    assert_eq!(layout.hex_to_pixel(hex), vertices[0]);
+    assert_eq!(&layout.polygon_corners(hex)[..], &vertices[1..]);
+    assert_eq!(6, triangles.len());
+
+    // Every triangle is wound the same way, and together they
+    // have exactly the area of the hex, so they tile it.
+    let area = |[a, b, c]: [u16; 3]| {
+        let (a, b, c) = (a as usize, b as usize, c as usize);
+        assert!(a < 7 && b < 7 && c < 7);
+        let (x0, y0) = vertices[a];
+        let (x1, y1) = vertices[b];
+        let (x2, y2) = vertices[c];
+        ((x1 - x0) * (y2 - y0) - (x2 - x0) * (y1 - y0)) / 2.0
+    };
+    let areas: Vec<f64> = triangles.iter().map(|&t| area(t)).collect();
+    assert!(areas.iter().all(|&a| a > 0.0));
+    let hex_area = 3.0 * 3.0f64.sqrt() / 8.0 * 20.0 * 30.0;
+    assert!((areas.iter().sum::<f64>() - hex_area).abs() < 1e-9);
+}
+
+#[test]
+fn test_edges() {
+    let hex = HexCoord::new(1i32, 2i32);
+    let layout = Layout::new((10.0f64, 10.0), (5.0, -5.0));
+    let corners = layout.polygon_corners(hex);
+    let center = layout.hex_to_pixel(hex);
+    for (i, &(d, p0, p1)) in layout.edges(hex).iter().enumerate() {
+        assert_eq!(corners[i], p0);
+        assert_eq!(corners[(i + 1) % 6], p1);
+        // The neighbor's center is the reflection of this
+        // center across the edge midpoint.
+        let mid = ((p0.0 + p1.0) / 2.0, (p0.1 + p1.1) / 2.0);
+        let (nx, ny) = layout.hex_to_pixel(hex.neighbor(d));
+        assert!((2.0 * mid.0 - center.0 - nx).abs() < 1e-9);
+        assert!((2.0 * mid.1 - center.1 - ny).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_hexes_in_rect() {
+    let layout = Layout::new((20.0f64, -20.0), (7.0, 3.0));
+    let (min, max) = ((-35.0, -12.0), (52.0, 40.0));
+    let hexes: HexSet<i32> = layout.hexes_in_rect(min, max).collect();
+    let inside = |(x, y): (f64, f64)| {
+        min.0 < x && x < max.0 && min.1 < y && y < max.1
+    };
+    let norm = |(x, y): (f64, f64)| (x * x + y * y).sqrt();
+    for hex in shapes::range(HexCoord::new(0, 0), 12) {
+        let c = layout.hex_to_pixel(hex);
+        if inside(c) {
+            assert!(hexes.contains(hex));
+        }
+        let corner_inside =
+            layout.polygon_corners(hex).iter().any(|&p| inside(p));
+        if corner_inside {
+            assert!(hexes.contains(hex));
+        }
+        if hexes.contains(hex) {
+            // Within a circumradius of the rectangle.
+            let dx = (min.0 - c.0).max(c.0 - max.0).max(0.0);
+            let dy = (min.1 - c.1).max(c.1 - max.1).max(0.0);
+            assert!(norm((dx, dy)) < 10.0 + 1e-9);
+        }
+    }
+
+    // This rectangle spans the edge between `(-1, -1)` and
+    // `(-1, 0)`, and touches the west corner of `(0, 0)` and
+    // the east corner of `(-2, -1)`, which are excluded.
+    let unit = Layout::<f64>::default();
+    let edge: Vec<HexCoord<i32>> =
+        unit.hexes_in_rect((-1.0, -0.05), (-0.5, 0.05)).collect();
+    assert_eq!(vec![HexCoord::new(-1, -1), HexCoord::new(-1, 0)], edge);
+}
+
+#[test]
+fn test_handedness() {
+    use Direction::*;
+    let right = Layout::new((20.0f64, 30.0), (100.0, 50.0));
+    assert_eq!(right, right.with_handedness(Handedness::Right));
+    let left = right.with_handedness(Handedness::Left);
+    let h = HexCoord::new;
+    for &(q, r) in &[(0i32, 0i32), (0, 1), (2, -1), (-3, 4)] {
+        let hex = h(q, r);
+        let mirror = Handedness::Left.to_right(hex);
+        assert_eq!(hex, Handedness::Left.from_right(mirror));
+        assert_eq!(right.hex_to_pixel(mirror), left.hex_to_pixel(hex));
+        assert_eq!(hex, left.pixel_to_hex(left.hex_to_pixel(hex)));
+
+        // Mirrored north to south about the origin's row.
+        let (rx, ry) = right.hex_to_pixel(hex);
+        let (lx, ly) = left.hex_to_pixel(hex);
+        assert_eq!(rx, lx);
+        assert!((ry - 50.0 + ly - 50.0).abs() < 1e-9);
+
+        let center = left.hex_to_pixel(hex);
+        for &(d, p0, p1) in &left.edges(hex) {
+            let mid = ((p0.0 + p1.0) / 2.0, (p0.1 + p1.1) / 2.0);
+            let (nx, ny) = left.hex_to_pixel(hex.neighbor(d));
+            assert!((2.0 * mid.0 - center.0 - nx).abs() < 1e-9);
+            assert!((2.0 * mid.1 - center.1 - ny).abs() < 1e-9);
+        }
+    }
+
+    // Left-handed `r` increases to the south.
+    let north = h(0, 0).neighbor(Handedness::Left.direction(N));
+    assert_eq!(h(0, -1), north);
+    let (_, y0) = left.hex_to_pixel(h(0, 0));
+    let (_, y1) = left.hex_to_pixel(h(0, 1));
+    assert!(y1 < y0);
+    for &d in &DIRECTIONS {
+        assert_eq!(d, Handedness::Right.direction(d));
+    }
+
+    let (min, max) = ((-35.0, -12.0), (152.0, 140.0));
+    let hexes: HexSet<i32> = left.hexes_in_rect(min, max).collect();
+    for hex in shapes::range(h(0, 0), 8) {
+        let (x, y) = left.hex_to_pixel(hex);
+        if min.0 < x && x < max.0 && min.1 < y && y < max.1 {
+            assert!(hexes.contains(hex));
+        }
+    }
+}
+
+#[test]
+fn test_bucket_points() {
+    let layout = Layout::new((20.0f64, -20.0), (7.0, 3.0));
+    let h = HexCoord::new;
+    let (a, b) = (h(0i32, 0i32), h(2, -1));
+    let near = |hex, (dx, dy)| {
+        let (x, y) = layout.hex_to_pixel(hex);
+        (x + dx, y + dy)
+    };
+    let points = [
+        near(a, (0.0, 0.0)),
+        near(b, (3.0, -2.0)),
+        near(a, (-4.0, 5.0)),
+        near(b, (0.0, 0.0)),
+        near(a, (6.0, 0.0)),
+    ];
+    let buckets = layout.bucket_points(&points);
+    assert_eq!(2, buckets.len());
+    assert_eq!(Some(&vec![0, 2, 4]), buckets.get(a));
+    assert_eq!(Some(&vec![1, 3]), buckets.get(b));
+    assert!(layout.bucket_points::<i32>(&[]).is_empty());
+}
+
+#[test]
+fn test_layout_mirrored() {
+    let up = Layout::new((20.0f64, 30.0), (100.0, 50.0));
+    let down = Layout::new((20.0f64, -30.0), (100.0, 50.0));
+    for hex in shapes::range(HexCoord::new(1i32, -1i32), 3) {
+        let (ux, uy) = up.hex_to_pixel(hex);
+        let (dx, dy) = down.hex_to_pixel(hex);
+        assert_eq!(ux, dx);
+        assert!((uy - 50.0 + dy - 50.0).abs() < 1e-9);
+        assert_eq!(hex, down.pixel_to_hex((dx, dy)));
+        let ucorners = up.polygon_corners(hex);
+        let dcorners = down.polygon_corners(hex);
+        for (&(ux, uy), &(dx, dy)) in
+            ucorners.iter().zip(dcorners.iter())
+        {
+            assert_eq!(ux, dx);
+            assert!((uy - 50.0 + dy - 50.0).abs() < 1e-9);
+        }
+        // A point just inside a corner stays in the hex.
+        let (cx, cy) = dcorners[1];
+        let p = (dx + 0.9 * (cx - dx), dy + 0.9 * (cy - dy));
+        assert_eq!(hex, down.pixel_to_hex(p));
+    }
+    // North is up on a `y`-down screen.
+    let north = HexCoord::new(0i32, 1i32);
+    assert!(down.hex_to_pixel(north).1 < down.origin.1);
+}
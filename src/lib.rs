@@ -7,7 +7,9 @@ This code is currently opinionated. The crate exposes q-r
 axial coordinates as the primary coordinate type, in a
 "right-handed" (*q* increasing east, *r* increasing north)
 flat-topped coordinate system.  It also provides cube
-coordinates and flat-topped hexes.
+coordinates and flat-topped hexes. The axial hex `(q, r)`
+is the cube hex `(q, r - q, -r)`: since *r* increases
+north, it runs along the negative cube *z* axis.
 
 Pointy-topped hexes and various other coordinate systems
 should probably be an option: patches welcome.
@@ -23,11 +25,73 @@ use std::convert::TryFrom;
 use std::fmt::Debug;
 
 pub use num;
-use num::{Float, Num};
+use num::{CheckedAdd, CheckedSub, Float, Num, NumCast, ToPrimitive};
 
 use thiserror::Error;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// Constant macros for Cartesian coordinate calculations.
+macro_rules! nc {
+    ($numstr:literal, $u:ty) => {
+        num_const::<$u>($numstr)
+    };
+}
+
+macro_rules! half {
+    ($u:ty) => {
+        nc!("0.5", $u)
+    };
+}
+
+macro_rules! quarter {
+    ($u:ty) => {
+        nc!("0.25", $u)
+    };
+}
+
+macro_rules! sqrt3 {
+    ($u:ty) => {
+        nc!("3.0", $u).sqrt()
+    };
+}
+
+macro_rules! sqrt3d2 {
+    ($u:ty) => {
+        half!($u) * sqrt3!($u)
+    };
+}
+
+mod bounds;
+pub use bounds::{HexBounds, OffsetBounds};
+mod camera;
+pub use camera::Camera;
+mod direction_map;
+pub use direction_map::DirectionMap;
+mod fractional;
+pub use fractional::{project_to_plane, FractionalCube, RoundMode};
+mod frame;
+pub use frame::Frame;
+mod hexmap;
+pub use hexmap::HexMap;
+mod hexset;
+pub use hexset::HexSet;
+mod key;
+pub use key::{canonical_edge, HexKey};
+mod layout;
+pub use layout::{Edge, Handedness, Layout};
+mod offset;
+pub use offset::{OffsetCoord, OffsetKind};
+mod wrap;
+pub use wrap::WrapGrid;
+
+pub mod cartesian;
+pub mod chunk;
+pub mod line;
+pub mod path;
+pub mod shapes;
+#[cfg(feature = "svg")]
+pub mod svg;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 /// "Compass" directions on the flat-topped hex grid.
 pub enum Direction {
     /// Northeast
@@ -44,12 +108,49 @@ pub enum Direction {
     SE,
 }
 
+/// The three cube coordinate axes, named after the
+/// components they measure: `Q` for cube `x` (axial `q`),
+/// `R` for cube `z` (axial `r`, negated) and `S` for cube
+/// `y`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// Cube `x`
+    Q,
+    /// Cube `z`
+    R,
+    /// Cube `y`
+    S,
+}
+
+/// All directions, in declaration (counterclockwise) order.
+pub(crate) const DIRECTIONS: [Direction; 6] = {
+    use Direction::*;
+    [NE, N, NW, SW, S, SE]
+};
+
+/// Axial `(q, r)` offset of the neighbor in each direction,
+/// indexed by `usize::from(d)` (equivalently
+/// `Direction::try_from(i)`).
+pub const DIRECTION_DELTAS: [(i8, i8); 6] =
+    [(1, 1), (0, 1), (-1, 0), (-1, -1), (0, -1), (1, 0)];
+
 /// Error indicating that specified direction coordinate
 /// is out of range.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
 #[error("direction error: {}", .0)]
 pub struct DirectionError(pub usize);
 
+#[test]
+fn test_direction_deltas() {
+    let hex = HexCoord::new(3i32, -4i32);
+    for (i, &(dq, dr)) in DIRECTION_DELTAS.iter().enumerate() {
+        let d = Direction::try_from(i).unwrap();
+        assert_eq!(i, d.into());
+        let n = HexCoord::new(hex.q + dq as i32, hex.r + dr as i32);
+        assert_eq!(hex.neighbor(d), n);
+    }
+}
+
 #[test]
 fn test_direction_error() {
     let err = Direction::try_from(7).err().unwrap();
@@ -59,12 +160,210 @@ fn test_direction_error() {
 impl TryFrom<usize> for Direction {
     type Error = DirectionError;
     fn try_from(d: usize) -> Result<Self, Self::Error> {
-        use Direction::*;
-        const DIRNS: [Direction; 6] = [N, NW, SW, S, SE, NE];
-        if d >= DIRNS.len() {
-            return Err(DirectionError(d));
+        DIRECTIONS.get(d).copied().ok_or(DirectionError(d))
+    }
+}
+
+impl Direction {
+    /// All six directions, in declaration (counterclockwise)
+    /// order starting with `Direction::NE`: the order of
+    /// `d as usize`.
+    pub fn all() -> [Direction; 6] {
+        DIRECTIONS
+    }
+
+    /// Direction with index `i` reduced modulo 6, so that
+    /// any integer names a direction: `-1` is
+    /// `Direction::SE`, say. See `TryFrom<usize>` for a
+    /// checked conversion.
+    pub fn from_index_wrapping(i: i32) -> Direction {
+        DIRECTIONS[i.rem_euclid(6) as usize]
+    }
+
+    /// The direction `self` becomes under
+    /// `HexCubeCoord::reflect(axis)`.
+    pub fn reflect(self, axis: Axis) -> Direction {
+        let origin = HexCoord::new(0i8, 0i8);
+        let image =
+            HexCubeCoord::from(origin.neighbor(self)).reflect(axis);
+        origin.direction_to(image.into()).unwrap()
+    }
+
+    /// Bearing of `self` in radians, counterclockwise from
+    /// east in the Cartesian plane of
+    /// `HexCoord::cartesian_center()`: `Direction::NE` is
+    /// at π/6, `Direction::N` at π/2, and so on.
+    pub fn angle<U: Float>(self) -> U {
+        let sixth = U::from(std::f64::consts::FRAC_PI_3).unwrap();
+        (half!(U) + U::from(self as u8).unwrap()) * sixth
+    }
+
+    /// The direction whose bearing (see `angle()`) is
+    /// nearest to `radians`, measured counterclockwise from
+    /// east. Any finite angle is accepted; a NaN or infinite
+    /// angle has no nearest direction and gives
+    /// `Direction::NE`. An angle exactly midway between two
+    /// directions, such as `0` (east), goes to the
+    /// counterclockwise one. The other midpoints are
+    /// multiples of π/3 that floating point only
+    /// approximates, so rounding decides the tie:
+    /// `from_angle(PI)` in `f64` gives `Direction::NW`.
+    pub fn from_angle<U: Float>(radians: U) -> Direction {
+        if !radians.is_finite() {
+            return Direction::NE;
+        }
+        // Reduce angles beyond a turn, so that the sector
+        // count of a huge angle fits an integer. A remainder
+        // by a rounded 2π would drift for huge angles, but
+        // `sin()` and `cos()` reduce exactly.
+        let mut turn = radians;
+        if turn.abs() > U::from(std::f64::consts::TAU).unwrap() {
+            turn = turn.sin().atan2(turn.cos());
+        }
+        let sixth = U::from(std::f64::consts::FRAC_PI_3).unwrap();
+        let i = (turn / sixth).floor().to_i32().unwrap_or(0);
+        Direction::from_index_wrapping(i)
+    }
+
+    /// The direction nearest that of the Cartesian offset
+    /// `(dx, dy)`, as for `from_angle()`: the neighbor whose
+    /// center the offset most points toward. An offset
+    /// exactly midway between two neighbors goes to the
+    /// counterclockwise one, as for `from_angle()`: due east
+    /// `(1, 0)`, between `SE` and `NE`, gives
+    /// `Direction::NE`. The other five midway bearings are
+    /// multiples of π/3, which floating point cannot
+    /// represent exactly, so rounding decides which side of
+    /// the midpoint they fall on: due west `(-1, 0)` gives
+    /// `Direction::NW` in `f64` but `Direction::SW` in
+    /// `f32`. A zero offset gives `Direction::NE`.
+    pub fn from_offset<U: Float>(dx: U, dy: U) -> Direction {
+        Direction::from_angle(dy.atan2(dx))
+    }
+
+    /// Direction `steps` 60° turns clockwise of `self`;
+    /// negative `steps` turn counterclockwise.
+    pub fn rotate(self, steps: i8) -> Direction {
+        let i = (self as i32 - steps as i32).rem_euclid(6);
+        DIRECTIONS[i as usize]
+    }
+
+    /// Signed number of 60° turns taking `self` to `target`
+    /// by the shortest rotation, in the sense of `rotate()`:
+    /// positive clockwise, negative counterclockwise. An
+    /// about-face is `3`.
+    pub fn turn_to(self, target: Direction) -> i8 {
+        let cw = (self as i8 - target as i8).rem_euclid(6);
+        if cw > 3 {
+            cw - 6
+        } else {
+            cw
         }
-        Ok(DIRNS[d])
+    }
+
+    /// The direction pointing the opposite way from `self`.
+    pub fn opposite(self) -> Direction {
+        DIRECTIONS[(self as usize + 3) % 6]
+    }
+}
+
+#[test]
+fn test_from_index_wrapping() {
+    use Direction::*;
+    for (i, &d) in DIRECTIONS.iter().enumerate() {
+        assert_eq!(d, Direction::from_index_wrapping(i as i32));
+        assert_eq!(d, Direction::from_index_wrapping(i as i32 - 6));
+        assert_eq!(d, Direction::from_index_wrapping(i as i32 + 600));
+    }
+    assert_eq!(SE, Direction::from_index_wrapping(-1));
+    assert_eq!(NW, Direction::from_index_wrapping(-10));
+    assert_eq!(NE, Direction::from_index_wrapping(i32::MIN + 2));
+    assert_eq!(N, Direction::from_index_wrapping(i32::MAX));
+}
+
+#[test]
+fn test_from_angle() {
+    use std::f64::consts::PI;
+    use Direction::*;
+    for &d in &DIRECTIONS {
+        let a: f64 = d.angle();
+        assert_eq!(d, Direction::from_angle(a));
+        assert_eq!(d, Direction::from_angle(a + 2.0 * PI));
+        assert_eq!(d, Direction::from_angle(a - 4.0 * PI));
+        assert_eq!(d, Direction::from_angle(a as f32));
+        // Just either side of the midpoint counterclockwise.
+        let mid = a + PI / 6.0;
+        assert_eq!(d, Direction::from_angle(mid - 1e-9));
+        assert_eq!(d.rotate(-1), Direction::from_angle(mid + 1e-9));
+    }
+    assert!((N.angle::<f64>() - PI / 2.0).abs() < 1e-15);
+    assert_eq!(NE, Direction::from_angle(0.0f64));
+    assert_eq!(SE, Direction::from_angle(-1e-9f64));
+    assert_eq!(N, Direction::from_angle(PI / 2.0));
+    assert_eq!(S, Direction::from_angle(-PI / 2.0));
+    assert_eq!(NW, Direction::from_angle(PI));
+    assert_eq!(NE, Direction::from_angle(f64::NAN));
+    // Too many sixths of a turn for an `i64`.
+    for &big in &[1e300f64, -1e300, 7e21] {
+        let expected = Direction::from_offset(big.cos(), big.sin());
+        assert_eq!(expected, Direction::from_angle(big));
+    }
+    assert_eq!(SE, Direction::from_angle(-1e-20f64));
+    assert_eq!(NE, Direction::from_angle(f32::NEG_INFINITY));
+}
+
+#[test]
+fn test_from_offset() {
+    let hex = HexCoord::new(-1i32, 2i32);
+    let (x, y) = hex.cartesian_center::<f64>();
+    for &d in &DIRECTIONS {
+        let (nx, ny) = hex.neighbor(d).cartesian_center::<f64>();
+        let (dx, dy) = (nx - x, ny - y);
+        assert_eq!(d, Direction::from_offset(dx, dy));
+        assert_eq!(d, Direction::from_offset(1e-3 * dx, 1e-3 * dy));
+        // Off-axis offsets still point to the nearest.
+        let (c, s) = (0.9f64.cos(), 0.9f64.sin());
+        let turned = (dx * c - dy * s, dx * s + dy * c);
+        assert_eq!(
+            d.rotate(-1),
+            Direction::from_offset(turned.0, turned.1)
+        );
+    }
+    assert_eq!(Direction::NE, Direction::from_offset(0.0f32, 0.0));
+
+    // Ties between neighbors go counterclockwise.
+    use Direction::*;
+    assert_eq!(NE, Direction::from_offset(1.0f64, 0.0));
+    let rounded = Direction::from_offset(0.5f64, 0.75f64.sqrt());
+    assert!(rounded == NE || rounded == N);
+    assert_eq!(NW, Direction::from_offset(-1.0f64, 0.0));
+    assert_eq!(SW, Direction::from_offset(-1.0f32, 0.0));
+    assert_eq!(N, Direction::from_angle(std::f64::consts::FRAC_PI_3));
+}
+
+#[test]
+fn test_rotate() {
+    use Direction::*;
+    assert_eq!(SE, NE.rotate(1));
+    assert_eq!(N, NE.rotate(-1));
+    assert_eq!(SW, NE.rotate(3));
+    assert_eq!(NE, NE.rotate(6));
+    assert_eq!(S, N.rotate(-9));
+}
+
+#[test]
+fn test_turn_to() {
+    use Direction::*;
+    assert_eq!(1, NE.turn_to(SE));
+    assert_eq!(-1, NE.turn_to(N));
+    assert_eq!(3, N.turn_to(S));
+    for &from in &DIRECTIONS {
+        for &to in &DIRECTIONS {
+            let turn = from.turn_to(to);
+            assert!(turn.abs() <= 3);
+            assert_eq!(to, from.rotate(turn));
+        }
+        assert_eq!(0, from.turn_to(from));
     }
 }
 
@@ -74,6 +373,24 @@ impl From<Direction> for usize {
     }
 }
 
+fn abs_diff<T: Num + PartialOrd>(a: T, b: T) -> T {
+    if a <= b {
+        b - a
+    } else {
+        a - b
+    }
+}
+
+// Floor of `a / b` for positive `b`.
+fn div_floor<T: num::PrimInt + num::Signed>(a: T, b: T) -> T {
+    let d = a / b;
+    if (a % b).is_negative() {
+        d - T::one()
+    } else {
+        d
+    }
+}
+
 fn num_const<T: Num>(s: &str) -> T {
     T::from_str_radix(s, 10)
         .unwrap_or_else(|_| panic!("no {} for numeric type", s))
@@ -91,35 +408,57 @@ pub struct HexCoord<T> {
     pub r: T,
 }
 
-// Constant macros for Cartesian coordinate calculations.
-macro_rules! nc {
-    ($numstr:literal, $u:ty) => {
-        num_const::<$u>($numstr)
-    };
-}
-
-macro_rules! half {
-    ($u:ty) => {
-        nc!("0.5", $u)
-    };
-}
+/// The offsets of the six corners of a unit-width hex from
+/// its center, in the order of `HexCoord::cartesian_corners()`.
+/// Building this evaluates the numeric constants and square
+/// root once; when finding the corners of many hexes, as on
+/// a redraw, build it once and use `corners()` for each hex
+/// rather than calling `cartesian_corners()`. The results
+/// are identical.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CornerOffsets<U>([(U, U); 6]);
 
-macro_rules! quarter {
-    ($u:ty) => {
-        nc!("0.25", $u)
-    };
-}
+impl<U: Float> CornerOffsets<U> {
+    /// Compute the corner offsets for number type `U`.
+    pub fn new() -> Self {
+        let h = half!(U);
+        let q = quarter!(U);
+        let dy = h * sqrt3d2!(U);
+        let z = U::zero();
+        CornerOffsets([
+            (h, z),
+            (q, dy),
+            (-q, dy),
+            (-h, z),
+            (-q, -dy),
+            (q, -dy),
+        ])
+    }
 
-macro_rules! sqrt3 {
-    ($u:ty) => {
-        nc!("3.0", $u).sqrt()
-    };
+    /// `hex.cartesian_corners()`, using these offsets.
+    pub fn corners<T: Num + Into<U>>(
+        &self,
+        hex: HexCoord<T>,
+    ) -> [(U, U); 6] {
+        let (x, y) = hex.cartesian_center();
+        let d = &self.0;
+        // The east and west corners share the center's `y`
+        // exactly.
+        [
+            (d[0].0 + x, y),
+            (d[1].0 + x, d[1].1 + y),
+            (d[2].0 + x, d[2].1 + y),
+            (d[3].0 + x, y),
+            (d[4].0 + x, d[4].1 + y),
+            (d[5].0 + x, d[5].1 + y),
+        ]
+    }
 }
 
-macro_rules! sqrt3d2 {
-    ($u:ty) => {
-        half!($u) * sqrt3!($u)
-    };
+impl<U: Float> Default for CornerOffsets<U> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T: Num> HexCoord<T> {
@@ -130,6 +469,15 @@ impl<T: Num> HexCoord<T> {
         HexCoord { q, r }
     }
 
+    /// Convert the coordinates of `self` to another number
+    /// type, for example `HexCoord<i16>` to `HexCoord<f32>`.
+    pub fn cast<U: Num>(self) -> HexCoord<U>
+    where
+        T: Into<U>,
+    {
+        HexCoord::new(self.q.into(), self.r.into())
+    }
+
     /// Axial coordinate of hex neighboring `self` in
     /// direction `d`.
     pub fn neighbor(self, d: Direction) -> Self {
@@ -148,6 +496,111 @@ impl<T: Num> HexCoord<T> {
         }
     }
 
+    /// Axial coordinate of the hex `n` steps from `self` in
+    /// direction `d`: the same as `n` chained calls to
+    /// `neighbor(d)`, with negative `n` stepping backward.
+    pub fn step(self, d: Direction, n: T) -> Self
+    where
+        T: Clone,
+    {
+        use Direction::*;
+        let (q, r) = (self.q, self.r);
+        match d {
+            NE => HexCoord::new(q + n.clone(), r + n),
+            N => HexCoord::new(q, r + n),
+            NW => HexCoord::new(q - n, r),
+            SW => HexCoord::new(q - n.clone(), r - n),
+            S => HexCoord::new(q, r - n),
+            SE => HexCoord::new(q + n, r),
+        }
+    }
+
+    /// Axial coordinate of hex neighboring `self` in
+    /// direction `d`, or `None` if a coordinate would
+    /// overflow `T`.
+    pub fn checked_neighbor(self, d: Direction) -> Option<Self>
+    where
+        T: CheckedAdd + CheckedSub,
+    {
+        use Direction::*;
+        let one = num::one::<T>();
+        let (q, r) = match d {
+            NE => {
+                (self.q.checked_add(&one)?, self.r.checked_add(&one)?)
+            }
+            N => (self.q, self.r.checked_add(&one)?),
+            NW => (self.q.checked_sub(&one)?, self.r),
+            SW => {
+                (self.q.checked_sub(&one)?, self.r.checked_sub(&one)?)
+            }
+            S => (self.q, self.r.checked_sub(&one)?),
+            SE => (self.q.checked_add(&one)?, self.r),
+        };
+        Some(HexCoord::new(q, r))
+    }
+
+    /// Direction from `self` to `other`, or `None` if
+    /// `other` is not a neighbor of `self`.
+    pub fn direction_to(self, other: Self) -> Option<Direction>
+    where
+        T: Copy,
+    {
+        DIRECTIONS
+            .iter()
+            .copied()
+            .find(|&d| self.neighbor(d) == other)
+    }
+
+    /// Iterator over the six neighbors of `self`, in
+    /// `Direction` declaration order.
+    pub fn neighbors(self) -> impl Iterator<Item = HexCoord<T>>
+    where
+        T: Clone,
+    {
+        DIRECTIONS.iter().map(move |&d| self.clone().neighbor(d))
+    }
+
+    /// Iterator over the six neighbors of `self`, each paired
+    /// with its direction, in `Direction` declaration order:
+    /// handy for building adjacency graphs.
+    pub fn adjacency(
+        self,
+    ) -> impl Iterator<Item = (Direction, HexCoord<T>)>
+    where
+        T: Clone,
+    {
+        DIRECTIONS
+            .iter()
+            .map(move |&d| (d, self.clone().neighbor(d)))
+    }
+
+    /// Iterator over the neighbors of `self` in the
+    /// directions of `order`, in that order: for example,
+    /// clockwise from north for deterministic tie-breaking.
+    pub fn neighbors_ordered<'a>(
+        self,
+        order: &'a [Direction],
+    ) -> impl Iterator<Item = HexCoord<T>> + 'a
+    where
+        T: Clone + 'a,
+    {
+        order.iter().map(move |&d| self.clone().neighbor(d))
+    }
+
+    /// Iterator over the neighbors of `self` for which
+    /// `inside` is true: typically a test that the neighbor
+    /// is on the board.
+    pub fn neighbors_in<F>(
+        self,
+        inside: F,
+    ) -> impl Iterator<Item = HexCoord<T>>
+    where
+        T: Clone,
+        F: Fn(HexCoord<T>) -> bool,
+    {
+        self.neighbors().filter(move |h| inside(h.clone()))
+    }
+
     /// "Manhattan distance" from `self` to `b`.
     pub fn distance(self, b: Self) -> T
     where
@@ -156,6 +609,51 @@ impl<T: Num> HexCoord<T> {
         HexCubeCoord::distance(self.into(), b.into())
     }
 
+    /// `distance()` computed in `i64`, for small integer
+    /// coordinate types. The cube coordinates and their
+    /// differences for hexes far apart can overflow `T`
+    /// even though the hexes themselves are representable.
+    pub fn distance_wide(self, b: Self) -> i64
+    where
+        T: Into<i64>,
+    {
+        let a = HexCoord::new(self.q.into(), self.r.into());
+        let b = HexCoord::new(b.q.into(), b.r.into());
+        a.distance(b)
+    }
+
+    /// Number of single-hex steps from `self` to `b`. This
+    /// is `distance()` as a count, computed as the largest
+    /// cube coordinate difference rather than by halving, and
+    /// rounded to the nearest step so that float coordinates
+    /// carrying a little accumulated error still give the
+    /// right answer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the distance is not representable as a
+    /// `usize`.
+    pub fn step_distance(self, b: Self) -> usize
+    where
+        T: PartialOrd + Clone + ToPrimitive,
+    {
+        let (ax, ay, az) = HexCubeCoord::from(self).coords();
+        let (bx, by, bz) = HexCubeCoord::from(b).coords();
+        let mut d = abs_diff(ax, bx);
+        for c in [abs_diff(ay, by), abs_diff(az, bz)] {
+            if c > d {
+                d = c;
+            }
+        }
+        let f = d.to_f64().expect("distance out of range");
+        let steps = if f.fract() == 0.0 {
+            d.to_usize()
+        } else {
+            f.round().to_usize()
+        };
+        steps.expect("distance out of range")
+    }
+
     /// `(x, y)` Cartesian coordinates of `HexCoord` center,
     /// for flat-topped pixels in a right-handed coordinate
     /// system (`x` increasing east, `y` increasing north)
@@ -171,25 +669,211 @@ impl<T: Num> HexCoord<T> {
         (x, y)
     }
 
+    /// Squared Euclidean distance between the centers of
+    /// `self` and `other` in the plane of
+    /// `cartesian_center()`, for comparing distances without
+    /// a square root. For axial differences `dq` and `dr`
+    /// this is `0.75 * (dq * dq - dq * dr + dr * dr)`.
+    pub fn center_distance_sq<U: Float>(self, other: Self) -> U
+    where
+        T: Into<U>,
+    {
+        let dq = self.q.into() - other.q.into();
+        let dr = self.r.into() - other.r.into();
+        num_const::<U>("0.75") * (dq * dq - dq * dr + dr * dr)
+    }
+
     /// `(x, y)` Cartesian coordinates of `HexCubeCoord`
     /// corners, for flat-topped pixels in a right-handed
     /// coordinate system (`x` increasing east, `y`
     /// increasing north) with hexes of unit width. Corners
     /// are given counterclockwise starting with the
-    /// easternmost.
+    /// easternmost. See `CornerOffsets` for finding the
+    /// corners of many hexes.
     pub fn cartesian_corners<U: Float>(self) -> [(U, U); 6]
+    where
+        T: Into<U>,
+    {
+        CornerOffsets::new().corners(self)
+    }
+
+    /// The corners of `cartesian_corners()` in clockwise
+    /// order, still starting with the easternmost, for
+    /// consumers that expect clockwise winding.
+    pub fn cartesian_corners_cw<U: Float>(self) -> [(U, U); 6]
+    where
+        T: Into<U>,
+    {
+        let c = self.cartesian_corners();
+        [c[0], c[5], c[4], c[3], c[2], c[1]]
+    }
+
+    /// `cartesian_center()` as a `mint` point, for handing
+    /// to math libraries that accept `mint` types.
+    #[cfg(feature = "mint")]
+    pub fn cartesian_center_mint<U: Float>(self) -> mint::Point2<U>
     where
         T: Into<U>,
     {
         let (x, y) = self.cartesian_center();
-        [
-            (half!(U) + x, y),
-            (quarter!(U) + x, half!(U) * sqrt3d2!(U) + y),
-            (-quarter!(U) + x, half!(U) * sqrt3d2!(U) + y),
-            (-half!(U) + x, y),
-            (-quarter!(U) + x, -half!(U) * sqrt3d2!(U) + y),
-            (quarter!(U) + x, -half!(U) * sqrt3d2!(U) + y),
-        ]
+        mint::Point2 { x, y }
+    }
+
+    /// `cartesian_corners()` as `mint` points.
+    #[cfg(feature = "mint")]
+    pub fn cartesian_corners_mint<U: Float>(
+        self,
+    ) -> [mint::Point2<U>; 6]
+    where
+        T: Into<U>,
+    {
+        self.cartesian_corners().map(|(x, y)| mint::Point2 { x, y })
+    }
+
+    /// Axial coordinate of the hex containing the point
+    /// `(x, y)`, in the Cartesian coordinate system of
+    /// `cartesian_center()`. This is the inverse of that
+    /// function for hex centers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the containing hex is not representable in
+    /// `T`.
+    pub fn from_cartesian<U: Float>((x, y): (U, U)) -> Self
+    where
+        T: NumCast,
+    {
+        let q = x / num_const::<U>("0.75");
+        let r = y / sqrt3d2!(U) + half!(U) * q;
+        HexCubeCoord::new_unchecked(q, r - q, -r).round().into()
+    }
+
+    /// Flat-topped axial coordinate of the hex at
+    /// pointy-topped axial coordinate `(q, r)`. The
+    /// pointy-topped coordinates are taken in the Red Blob
+    /// Games convention: `q` increasing east and `r`
+    /// increasing southeast. Rotating that grid 30°
+    /// clockwise makes it flat-topped, with its `q` axis
+    /// pointing southeast and its `r` axis pointing south;
+    /// negating `r` then gives this crate's coordinates.
+    /// So `(q, r)` becomes `HexCoord::new(q, -r)`.
+    pub fn from_pointy(q: T, r: T) -> Self {
+        HexCoord::new(q, num::zero::<T>() - r)
+    }
+
+    /// Pointy-topped axial coordinate `(q, r)` of `self`.
+    /// This is the inverse of `from_pointy()`, which see.
+    pub fn to_pointy(self) -> (T, T) {
+        (self.q, num::zero::<T>() - self.r)
+    }
+}
+
+#[test]
+fn test_pointy() {
+    for q in -3i32..=3 {
+        for r in -3i32..=3 {
+            let hex = HexCoord::from_pointy(q, r);
+            assert_eq!((q, r), hex.to_pointy());
+            let (pq, pr) = hex.to_pointy();
+            assert_eq!(hex, HexCoord::from_pointy(pq, pr));
+
+            // Red Blob pointy-topped center for unit-width
+            // hexes, flipped to `y` increasing north and
+            // rotated 30° clockwise.
+            let (q, r) = (q as f64, r as f64);
+            let size = 0.5;
+            let px =
+                size * (3.0f64.sqrt() * q + 3.0f64.sqrt() / 2.0 * r);
+            let py = -size * 1.5 * r;
+            let (s, c) = (-30.0f64).to_radians().sin_cos();
+            let (x, y) = (px * c - py * s, px * s + py * c);
+            let (cx, cy): (f64, f64) = hex.cartesian_center();
+            assert!((x - cx).abs() < 1e-9 && (y - cy).abs() < 1e-9);
+        }
+    }
+}
+
+// Degenerate inputs to the iterator-style APIs: zero radii,
+// zero-length lines, and negative radii, which yield
+// nothing rather than panicking.
+#[cfg(test)]
+mod test_degenerate {
+    use crate::shapes::*;
+    use crate::*;
+
+    const CENTER: HexCoord<i32> = HexCoord { q: 3, r: -2 };
+
+    #[test]
+    fn test_zero_radius() {
+        let cube = HexCubeCoord::from(CENTER);
+        assert_eq!(vec![CENTER], range(CENTER, 0).collect::<Vec<_>>());
+        assert_eq!(vec![CENTER], ring(CENTER, 0).collect::<Vec<_>>());
+        assert_eq!(
+            vec![CENTER],
+            annulus(CENTER, 0, 0).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![cube], cube_range(cube, 0).collect::<Vec<_>>());
+        assert_eq!(vec![cube], cube_ring(cube, 0).collect::<Vec<_>>());
+        assert_eq!(
+            vec![cube],
+            cube_spiral(cube, 0).collect::<Vec<_>>()
+        );
+        let sixth: Vec<_> =
+            wedge(CENTER, 0, Direction::N, Direction::NW).collect();
+        assert_eq!(vec![CENTER], sixth);
+        assert_eq!(Some(CENTER), find_spiral(CENTER, 0, |_| true));
+        assert_eq!(1, ring_index_map(CENTER, 0).len());
+        // The center has no outward facing.
+        assert_eq!(0, ring_with_facing(CENTER, 0).count());
+    }
+
+    #[test]
+    fn test_zero_length_line() {
+        assert_eq!(vec![CENTER], CENTER.line_to(CENTER));
+        assert_eq!(vec![CENTER], CENTER.line_to_int(CENTER));
+        assert!(line::extend_line(CENTER, CENTER, 3).is_empty());
+        assert_eq!(0, line::distance_to_line(CENTER, CENTER, CENTER));
+        assert_eq!(
+            2,
+            line::distance_to_line(
+                CENTER.neighbor(Direction::N).neighbor(Direction::N),
+                CENTER,
+                CENTER
+            )
+        );
+    }
+
+    #[test]
+    fn test_negative_radius() {
+        let cube = HexCubeCoord::from(CENTER);
+        for &radius in &[-1, -7, i32::MIN] {
+            assert_eq!(0, range(CENTER, radius).count());
+            assert_eq!(0, ring(CENTER, radius).count());
+            assert_eq!(0, annulus(CENTER, radius, radius).count());
+            assert_eq!(0, cube_range(cube, radius).count());
+            assert_eq!(0, cube_ring(cube, radius).count());
+            assert_eq!(0, cube_spiral(cube, radius).count());
+            let w = wedge(CENTER, radius, Direction::N, Direction::NW);
+            assert_eq!(0, w.count());
+            assert_eq!(
+                0,
+                ring_within(CENTER, radius, CENTER, 5).count()
+            );
+            assert_eq!(0, ring_with_facing(CENTER, radius).count());
+            assert!(ring_index_map(CENTER, radius).is_empty());
+            assert_eq!(None, find_spiral(CENTER, radius, |_| true));
+        }
+        assert_eq!(0, annulus(CENTER, 3, 2).count());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_negative_radius_par() {
+        use rayon::iter::ParallelIterator;
+        for &radius in &[-1, i32::MIN] {
+            assert_eq!(0, par_range(CENTER, radius).count());
+            assert_eq!(0, par_ring(CENTER, radius).count());
+        }
     }
 }
 
@@ -264,6 +948,315 @@ mod test_cartesian {
         ];
         test(target, tcorners);
     }
+
+    #[test]
+    fn test_cartesian_corners_exact() {
+        fn reference<U: Float>(hex: HexCoord<i16>) -> [(U, U); 6]
+        where
+            i16: Into<U>,
+        {
+            let (x, y) = hex.cartesian_center();
+            [
+                (half!(U) + x, y),
+                (quarter!(U) + x, half!(U) * sqrt3d2!(U) + y),
+                (-quarter!(U) + x, half!(U) * sqrt3d2!(U) + y),
+                (-half!(U) + x, y),
+                (-quarter!(U) + x, -half!(U) * sqrt3d2!(U) + y),
+                (quarter!(U) + x, -half!(U) * sqrt3d2!(U) + y),
+            ]
+        }
+
+        let bits64 = |c: [(f64, f64); 6]| {
+            c.map(|(x, y)| (x.to_bits(), y.to_bits()))
+        };
+        let bits32 = |c: [(f32, f32); 6]| {
+            c.map(|(x, y)| (x.to_bits(), y.to_bits()))
+        };
+        let offsets64 = CornerOffsets::<f64>::new();
+        let offsets32 = CornerOffsets::<f32>::default();
+        for hex in shapes::range(HexCoord::new(0i16, 0), 12) {
+            let hex = HexCoord::new(hex.q * 37, hex.r * 53);
+            assert_eq!(
+                bits64(reference(hex)),
+                bits64(hex.cartesian_corners())
+            );
+            assert_eq!(
+                bits32(reference(hex)),
+                bits32(hex.cartesian_corners())
+            );
+            assert_eq!(
+                bits64(reference(hex)),
+                bits64(offsets64.corners(hex))
+            );
+            assert_eq!(
+                bits32(reference(hex)),
+                bits32(offsets32.corners(hex))
+            );
+        }
+    }
+
+    #[test]
+    fn test_cartesian_corners_cw() {
+        // Twice the signed area: positive for counterclockwise.
+        fn area2(p: &[(f64, f64); 6]) -> f64 {
+            (0..6)
+                .map(|i| {
+                    let (x0, y0) = p[i];
+                    let (x1, y1) = p[(i + 1) % 6];
+                    x0 * y1 - x1 * y0
+                })
+                .sum()
+        }
+        let hex = HexCoord::new(2i32, -3i32);
+        let ccw = hex.cartesian_corners::<f64>();
+        let cw = hex.cartesian_corners_cw::<f64>();
+        assert_eq!(ccw[0], cw[0]);
+        for i in 1..6 {
+            assert_eq!(ccw[i], cw[6 - i]);
+        }
+        assert!(area2(&ccw) > 0.0);
+        assert!((area2(&ccw) + area2(&cw)).abs() < 1e-12);
+        let cube = HexCubeCoord::from(hex);
+        assert_eq!(cw, cube.cartesian_corners_cw::<f64>());
+    }
+
+    #[cfg(feature = "mint")]
+    #[test]
+    fn test_cartesian_mint() {
+        let hex = HexCoord::new(3i32, -2i32);
+        let p: mint::Point2<f64> = hex.cartesian_center_mint();
+        assert_eq!(hex.cartesian_center(), (p.x, p.y));
+        let back: [f64; 2] = p.into();
+        assert_eq!(hex, HexCoord::from_cartesian((back[0], back[1])));
+        let corners = hex.cartesian_corners_mint::<f64>();
+        for (c, &(x, y)) in
+            corners.iter().zip(hex.cartesian_corners::<f64>().iter())
+        {
+            assert_eq!((x, y), (c.x, c.y));
+        }
+    }
+
+    #[test]
+    fn test_center_distance_sq() {
+        let origin = HexCoord::new(1i32, -1i32);
+        let (ox, oy) = origin.cartesian_center::<f64>();
+        let mut hexes: Vec<_> = shapes::range(origin, 4).collect();
+        for &hex in &hexes {
+            let (x, y) = hex.cartesian_center::<f64>();
+            let d2 = (x - ox).powi(2) + (y - oy).powi(2);
+            let sq: f64 = origin.center_distance_sq(hex);
+            assert!((d2 - sq).abs() < 1e-9);
+            assert_eq!(sq, hex.center_distance_sq(origin));
+        }
+        let euclid = |h: &HexCoord<i32>| {
+            let (x, y) = h.cartesian_center::<f64>();
+            ((x - ox).powi(2) + (y - oy).powi(2)).sqrt()
+        };
+        hexes
+            .sort_by(|a, b| euclid(a).partial_cmp(&euclid(b)).unwrap());
+        let sq =
+            |h: &HexCoord<i32>| origin.center_distance_sq::<f64>(*h);
+        assert!(hexes
+            .windows(2)
+            .all(|w| sq(&w[0]) <= sq(&w[1]) + 1e-9));
+        assert_eq!(
+            0.75f64,
+            origin.center_distance_sq(origin.neighbor(Direction::N))
+        );
+    }
+
+    #[test]
+    fn test_from_cartesian() {
+        for q in -3..=3 {
+            for r in -3..=3 {
+                let hex = HexCoord::new(q, r);
+                let (x, y): (f64, f64) = hex.cartesian_center();
+                assert_eq!(hex, HexCoord::from_cartesian((x, y)));
+                for &(cx, cy) in &hex.cartesian_corners::<f64>() {
+                    let p = (x + 0.9 * (cx - x), y + 0.9 * (cy - y));
+                    assert_eq!(hex, HexCoord::from_cartesian(p));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_step_distance() {
+    use Direction::*;
+    let walks: &[&[(Direction, usize)]] = &[
+        &[],
+        &[(NE, 1)],
+        &[(NE, 3), (N, 2)],
+        &[(SW, 4), (S, 5)],
+        &[(SE, 2), (NE, 7)],
+        &[(NW, 6), (SW, 1)],
+    ];
+    for walk in walks {
+        let steps: usize = walk.iter().map(|&(_, n)| n).sum();
+        let start = HexCoord::new(-3i32, 5i32);
+        let startf = HexCoord::new(-3.0f32, 5.0f32);
+        let (mut end, mut endf) = (start, startf);
+        for &(d, n) in walk.iter() {
+            for _ in 0..n {
+                end = end.neighbor(d);
+                endf = endf.neighbor(d);
+            }
+        }
+        assert_eq!(steps, start.step_distance(end));
+        assert_eq!(steps, end.step_distance(start));
+        assert_eq!(steps, startf.step_distance(endf));
+    }
+
+    let mut q = 0.0f32;
+    for _ in 0..10 {
+        q += 0.1;
+    }
+    assert_ne!(1.0, q);
+    let origin = HexCoord::new(0.0f32, 0.0f32);
+    assert_eq!(1, origin.step_distance(HexCoord::new(q, 0.0)));
+    assert_eq!(2, origin.step_distance(HexCoord::new(q, -q)));
+}
+
+#[test]
+fn test_distance_wide() {
+    let a = HexCoord::new(2i8, -3i8);
+    let b = HexCoord::new(-1i8, 4i8);
+    assert_eq!(a.distance(b) as i64, a.distance_wide(b));
+
+    // Cube `y` alone is `200` here, which overflows `i8`.
+    let a = HexCoord::new(-100i8, 100i8);
+    let b = HexCoord::new(100i8, -100i8);
+    assert_eq!(400, a.distance_wide(b));
+    assert_eq!(400, b.distance_wide(a));
+    let a = HexCoord::new(i16::MIN, i16::MAX);
+    let b = HexCoord::new(i16::MAX, i16::MIN);
+    assert_eq!(4 * i16::MAX as i64 + 2, a.distance_wide(b));
+}
+
+#[test]
+fn test_cast() {
+    let hex = HexCoord::new(-300i16, 1200i16);
+    let wide: HexCoord<i32> = hex.cast();
+    assert_eq!(HexCoord::new(-300i32, 1200i32), wide);
+    let float: HexCoord<f64> = wide.cast();
+    assert_eq!(HexCoord::new(-300.0f64, 1200.0f64), float);
+
+    let cube = HexCubeCoord::from(hex);
+    let wide: HexCubeCoord<i32> = cube.cast();
+    assert_eq!(HexCubeCoord::from(hex.cast::<i32>()), wide);
+    let float: HexCubeCoord<f64> = wide.cast();
+    assert_eq!((-300.0, 1500.0, -1200.0), float.coords());
+}
+
+#[test]
+fn test_step() {
+    let hex = HexCoord::new(-3i32, 2i32);
+    for &d in &DIRECTIONS {
+        let chained = hex.neighbor(d).neighbor(d).neighbor(d);
+        assert_eq!(chained, hex.step(d, 3));
+        assert_eq!(hex, hex.step(d, 0));
+        assert_eq!(hex, chained.step(d, -3));
+        assert_eq!(3, hex.distance(hex.step(d, -3)));
+    }
+}
+
+#[test]
+fn test_checked_neighbor() {
+    let hex = HexCoord::new(3i8, -7i8);
+    for &d in &DIRECTIONS {
+        assert_eq!(Some(hex.neighbor(d)), hex.checked_neighbor(d));
+    }
+    let corner = HexCoord::new(i8::MAX, i8::MIN);
+    assert_eq!(None, corner.checked_neighbor(Direction::NE));
+    assert_eq!(None, corner.checked_neighbor(Direction::S));
+    assert_eq!(None, corner.checked_neighbor(Direction::SE));
+    assert_eq!(
+        Some(HexCoord::new(i8::MAX, i8::MIN + 1)),
+        corner.checked_neighbor(Direction::N),
+    );
+}
+
+#[test]
+fn test_wide_coordinates() {
+    use Direction::*;
+    let big = i64::MAX as i128;
+    let a = HexCoord::new(-big, big);
+    let b = HexCoord::new(big, -big);
+    assert_eq!(4 * big, a.distance(b));
+    assert!(a.distance(b) > i64::MAX as i128);
+    let cube_a = HexCubeCoord::from(a);
+    assert_eq!((-big, 2 * big, -big), cube_a.coords());
+    assert_eq!(a, HexCoord::from(cube_a));
+    assert_eq!(4 * big, cube_a.distance(HexCubeCoord::from(b)));
+    assert_eq!(HexCoord::new(1 - big, 1 + big), a.neighbor(NE));
+    assert_eq!(1, a.distance(a.neighbor(SW)));
+
+    let big: num::BigInt = num::BigInt::from(i128::MAX) * 3;
+    let a = HexCoord::new(big.clone(), num::BigInt::from(0));
+    let b = HexCoord::new(-big.clone(), big.clone());
+    assert_eq!(big.clone() * 3, a.clone().distance(b.clone()));
+    let cube_b = HexCubeCoord::from(b.clone());
+    assert_eq!(b, HexCoord::from(cube_b.clone()));
+    let n = b.clone().neighbor(S);
+    assert_eq!(big.clone() - 1, n.r);
+    assert_eq!(num::BigInt::from(1), b.distance(n));
+}
+
+#[test]
+fn test_direction_to() {
+    let hex = HexCoord::new(-2i32, 5i32);
+    for &d in &DIRECTIONS {
+        assert_eq!(Some(d), hex.direction_to(hex.neighbor(d)));
+    }
+    assert_eq!(None, hex.direction_to(hex));
+    assert_eq!(None, hex.direction_to(HexCoord::new(0, 5)));
+}
+
+#[test]
+fn test_neighbors_ordered() {
+    use Direction::*;
+    let hex = HexCoord::new(1i32, 1i32);
+    let clockwise = [N, NE, SE, S, SW, NW];
+    let ccw: Vec<_> = hex.neighbors_ordered(&DIRECTIONS).collect();
+    let cw: Vec<_> = hex.neighbors_ordered(&clockwise).collect();
+    assert_eq!(hex.neighbors().collect::<Vec<_>>(), ccw);
+    assert_ne!(ccw, cw);
+    assert_eq!(hex.neighbor(N), cw[0]);
+    assert_eq!(hex.neighbor(NE), cw[1]);
+    let (mut ccw, mut cw) = (ccw, cw);
+    ccw.sort();
+    cw.sort();
+    assert_eq!(ccw, cw);
+    assert_eq!(0, hex.neighbors_ordered(&[]).count());
+}
+
+#[test]
+fn test_neighbors() {
+    let hex = HexCoord::new(2i32, 3i32);
+    let neighbors: Vec<_> = hex.neighbors().collect();
+    let expected: Vec<_> =
+        DIRECTIONS.iter().map(|&d| hex.neighbor(d)).collect();
+    assert_eq!(expected, neighbors);
+    let adjacency: Vec<_> = hex.adjacency().collect();
+    assert_eq!(6, adjacency.len());
+    for (&d, &(ad, n)) in DIRECTIONS.iter().zip(adjacency.iter()) {
+        assert_eq!((d, hex.neighbor(d)), (ad, n));
+    }
+
+    let quadrant = |h: HexCoord<i32>| h.q >= 0 && h.r >= 0;
+    assert_eq!(6, hex.neighbors_in(quadrant).count());
+    let corner: Vec<_> =
+        HexCoord::new(0, 0).neighbors_in(quadrant).collect();
+    assert_eq!(
+        vec![
+            HexCoord::new(1, 1),
+            HexCoord::new(0, 1),
+            HexCoord::new(1, 0)
+        ],
+        corner,
+    );
+    assert_eq!(4, HexCoord::new(0, 2).neighbors_in(quadrant).count());
 }
 
 #[test]
@@ -278,13 +1271,69 @@ fn test_neighbor_axial() {
     assert_eq!(cur, start);
 }
 
+impl HexCoord<i16> {
+    /// The coordinate packed into a `u32` for compact
+    /// storage or transmission: the bits of `q` in the high
+    /// half and those of `r` in the low half.
+    pub fn pack(self) -> u32 {
+        ((self.q as u16 as u32) << 16) | self.r as u16 as u32
+    }
+
+    /// The coordinate packed by `pack()`.
+    pub fn unpack(packed: u32) -> Self {
+        HexCoord::new(
+            (packed >> 16) as u16 as i16,
+            packed as u16 as i16,
+        )
+    }
+
+    /// The packed coordinate as little-endian bytes: a wire
+    /// format that does not depend on the host.
+    pub fn to_le_bytes(self) -> [u8; 4] {
+        self.pack().to_le_bytes()
+    }
+
+    /// The coordinate encoded by `to_le_bytes()`.
+    pub fn from_le_bytes(bytes: [u8; 4]) -> Self {
+        Self::unpack(u32::from_le_bytes(bytes))
+    }
+}
+
+#[test]
+fn test_pack() {
+    let h = HexCoord::new(0x1234i16, -2);
+    assert_eq!(0x1234_fffe, h.pack());
+    assert_eq!([0xfe, 0xff, 0x34, 0x12], h.to_le_bytes());
+    let samples = [i16::MIN, i16::MIN + 1, -300, -1, 0, 1, 7, i16::MAX];
+    for &q in &samples {
+        for r in (i16::MIN..=i16::MAX).step_by(997).chain(samples) {
+            let h = HexCoord::new(q, r);
+            assert_eq!(h, HexCoord::unpack(h.pack()));
+            assert_eq!(h, HexCoord::from_le_bytes(h.to_le_bytes()));
+        }
+    }
+}
+
 /// Hex cube coordinates. This is opaque, to protect the
 /// coordinate invariant; this coordinate system
 /// is redundant, so the coordinate invariant
 ///
-/// > `x + y + z != 0`
+/// > `x + y + z == 0`
 ///
 /// is maintained internally.
+///
+/// Equality, ordering and hashing compare all three
+/// components, so a cube made with `new_unchecked()` that
+/// violates the invariant differs from the valid cube for
+/// the same hex. Call `canonical()` on such cubes before
+/// using them as keys, or use `HexKey`.
+///
+/// With floating-point components, the `y` computed when
+/// converting from axial coordinates is rounded, so the
+/// cube may be off the `x + y + z == 0` plane by a rounding
+/// error. Converting back to axial uses only `x` and `z`,
+/// so an axial coordinate survives the round trip through
+/// cube coordinates exactly.
 #[derive(
     Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
 )]
@@ -312,6 +1361,36 @@ pub struct CubeInvariantError<T: Num + std::fmt::Debug> {
     pub z: T,
 }
 
+impl<T: Num + std::fmt::Debug + Clone> CubeInvariantError<T> {
+    /// The sum `x + y + z`, which is nonzero for a genuine
+    /// violation: how far the coordinate is off the plane.
+    pub fn sum(&self) -> T {
+        self.x.clone() + self.y.clone() + self.z.clone()
+    }
+
+    /// The coordinate with the invariant restored by
+    /// subtracting `sum()` from the component of largest
+    /// magnitude, which is thus changed least in relative
+    /// terms. Ties go to `x`, then `y`.
+    pub fn corrected(&self) -> HexCubeCoord<T>
+    where
+        T: PartialOrd,
+    {
+        let sum = self.sum();
+        let (x, y, z) =
+            (self.x.clone(), self.y.clone(), self.z.clone());
+        let mag = |c: &T| abs_diff(c.clone(), T::zero());
+        let (mx, my, mz) = (mag(&x), mag(&y), mag(&z));
+        if mx >= my && mx >= mz {
+            HexCubeCoord::new_unchecked(x - sum, y, z)
+        } else if my >= mz {
+            HexCubeCoord::new_unchecked(x, y - sum, z)
+        } else {
+            HexCubeCoord::new_unchecked(x, y, z - sum)
+        }
+    }
+}
+
 #[test]
 fn test_cube_invariant_error() {
     let err = HexCubeCoord::new(1i8, 2i8, 3i8).err().unwrap();
@@ -319,6 +1398,19 @@ fn test_cube_invariant_error() {
         "cube invariant violation: x:1, y:2, z:3",
         err.to_string(),
     );
+    assert_eq!(6, err.sum());
+    assert_eq!((1, 2, -3), err.corrected().coords());
+
+    let err = HexCubeCoord::new(-7i32, 3, 5).err().unwrap();
+    assert_eq!(1, err.sum());
+    assert_eq!((-8, 3, 5), err.corrected().coords());
+
+    let err = HexCubeCoord::new(2i32, -4, 4).err().unwrap();
+    assert_eq!(2, err.sum());
+    let fixed = err.corrected();
+    assert_eq!((2, -6, 4), fixed.coords());
+    let (x, y, z) = fixed.coords();
+    assert!(HexCubeCoord::new(x, y, z).is_ok());
 }
 
 impl<T: Num> HexCubeCoord<T> {
@@ -347,30 +1439,170 @@ impl<T: Num> HexCubeCoord<T> {
         HexCubeCoord { x, y, z }
     }
 
+    /// Make a cube coordinate from the `(q, r, s)` of a Red
+    /// Blob Games `Hex`, checking the invariant. Their `q`
+    /// is this crate's `x`, their `r` is `z`, and their `s`
+    /// is `y`; any error reports the components in this
+    /// crate's `(x, y, z)` order. Red Blob Games pixel
+    /// coordinates have `y` increasing downward, so their
+    /// flat-topped `hex_to_pixel()` matches
+    /// `cartesian_center()` with `y` negated.
+    pub fn from_qrs(
+        q: T,
+        r: T,
+        s: T,
+    ) -> Result<HexCubeCoord<T>, CubeInvariantError<T>>
+    where
+        T: Clone + Debug,
+    {
+        HexCubeCoord::new(q, s, r)
+    }
+
+    /// Convert the coordinates of `self` to another number
+    /// type. The conversion is lossless, so the invariant
+    /// is preserved.
+    pub fn cast<U: Num>(self) -> HexCubeCoord<U>
+    where
+        T: Into<U>,
+    {
+        HexCubeCoord::new_unchecked(
+            self.x.into(),
+            self.y.into(),
+            self.z.into(),
+        )
+    }
+
     /// Return the cube coordinates.
     pub fn coords(self) -> (T, T, T) {
         (self.x, self.y, self.z)
     }
 
+    /// The cube `x` coordinate.
+    pub fn x(self) -> T {
+        self.x
+    }
+
+    /// The cube `y` coordinate.
+    pub fn y(self) -> T {
+        self.y
+    }
+
+    /// The cube `z` coordinate.
+    pub fn z(self) -> T {
+        self.z
+    }
+
+    /// The Red Blob Games `(q, r, s)` of `self`: the inverse
+    /// of `from_qrs()`, giving `(x, z, y)`.
+    pub fn to_qrs(self) -> (T, T, T) {
+        (self.x, self.z, self.y)
+    }
+
+    /// Axial coordinate of `self`, checking first that the
+    /// cube invariant holds: useful for cubes made with
+    /// `new_unchecked()`. The plain `From` conversion just
+    /// drops `y`.
+    pub fn to_axial_checked(
+        self,
+    ) -> Result<HexCoord<T>, CubeInvariantError<T>>
+    where
+        T: Clone + Debug,
+    {
+        let (x, y, z) = self.coords();
+        HexCubeCoord::new(x, y, z).map(HexCoord::from)
+    }
+
+    /// Mirror image of `self` in the line through the origin
+    /// along which the two components other than `axis` are
+    /// equal: those two components are swapped, and `axis`
+    /// is left unchanged.
+    pub fn reflect(self, axis: Axis) -> Self {
+        let (x, y, z) = (self.x, self.y, self.z);
+        match axis {
+            Axis::Q => HexCubeCoord::new_unchecked(x, z, y),
+            Axis::R => HexCubeCoord::new_unchecked(y, x, z),
+            Axis::S => HexCubeCoord::new_unchecked(z, y, x),
+        }
+    }
+
+    /// `self` with `y` recomputed from `x` and `z`, so that
+    /// the cube invariant holds. This keeps the axial
+    /// interpretation of `self`, which ignores `y`, and
+    /// never fails: a cheap repair for cubes made with
+    /// `new_unchecked()`.
+    pub fn canonicalize(self) -> Self
+    where
+        T: Clone,
+    {
+        let y = T::zero() - (self.x.clone() + self.z.clone());
+        HexCubeCoord::new_unchecked(self.x, y, self.z)
+    }
+
+    /// The canonical form of `self`: `canonicalize()` under
+    /// another name. Only canonical cubes should be used as
+    /// hash keys, since hashing compares all three
+    /// components.
+    #[inline]
+    pub fn canonical(self) -> Self
+    where
+        T: Clone,
+    {
+        self.canonicalize()
+    }
+
     /// "Manhattan distance" from `self` to `b`.
     pub fn distance(self, b: Self) -> T
     where
         T: PartialOrd,
     {
-        fn abs_diff<T: Num + PartialOrd>(a: T, b: T) -> T {
-            if a <= b {
-                b - a
-            } else {
-                a - b
-            }
-        }
-
         let x = abs_diff(self.x, b.x);
         let y = abs_diff(self.y, b.y);
         let z = abs_diff(self.z, b.z);
         (x + y + z) / num_const("2")
     }
 
+    /// Hex closest to the point midway between the centers
+    /// of `self` and `b`: the cube `lerp()` at `t == 0.5`,
+    /// rounded. When that point is not a hex center it lies
+    /// exactly on the edge between two hexes, as for two
+    /// neighbors. The tie then goes toward `self`, to
+    /// whichever of the two is nearer to it; so the midpoint
+    /// of neighbors is `self`. If both are equally near, as
+    /// when `self` and `b` lie on a line running along that
+    /// edge, the tie goes to the hex picked by `round()`,
+    /// in either order of the arguments.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a coordinate is not representable as an
+    /// `f64`.
+    pub fn midpoint(self, b: Self) -> Self
+    where
+        T: NumCast,
+    {
+        fn to_f64<T: NumCast>(c: HexCubeCoord<T>) -> HexCubeCoord<f64> {
+            let cast = |v: T| -> f64 {
+                num::cast(v).expect("coordinate not representable")
+            };
+            HexCubeCoord::new_unchecked(cast(c.x), cast(c.y), cast(c.z))
+        }
+        let a = to_f64(self);
+        let m = a.lerp(to_f64(b), 0.5);
+        let near: HexCubeCoord<f64> = m.round();
+        // The hex across the edge: the reflection of `near`
+        // through `m`, or `near` itself if `m` is its center.
+        let far = HexCubeCoord::new_unchecked(
+            2.0 * m.x - near.x,
+            2.0 * m.y - near.y,
+            2.0 * m.z - near.z,
+        );
+        if a.distance(far) < a.distance(near) {
+            far.round()
+        } else {
+            near.round()
+        }
+    }
+
     /// Coordinate of hex neighboring `self` in direction
     /// `d`. See `HexCoord::neighbor()` for details.
     pub fn neighbor(self, d: Direction) -> Self
@@ -397,6 +1629,16 @@ impl<T: Num> HexCubeCoord<T> {
     {
         <HexCoord<T>>::from(self).cartesian_corners()
     }
+
+    /// Cartesian coordinates of `HexCubeCoord` corners in
+    /// clockwise order. See `HexCoord::cartesian_corners_cw()`
+    /// for details.
+    pub fn cartesian_corners_cw<U: Float>(self) -> [(U, U); 6]
+    where
+        T: Into<U>,
+    {
+        <HexCoord<T>>::from(self).cartesian_corners_cw()
+    }
 }
 
 #[test]
@@ -411,6 +1653,137 @@ fn test_neighbor_cube() {
     assert_eq!(cur, start);
 }
 
+#[test]
+fn test_cube_accessors() {
+    let cube = HexCubeCoord::new(3i32, -5, 2).unwrap();
+    assert_eq!(cube.coords(), (cube.x(), cube.y(), cube.z()));
+}
+
+#[test]
+fn test_qrs() {
+    let cube = HexCubeCoord::from_qrs(1i32, -2, 1).unwrap();
+    assert_eq!((1, -2, 1), cube.to_qrs());
+    assert_eq!((1, 1, -2), cube.coords());
+    assert!(HexCubeCoord::from_qrs(1i32, 1, 1).is_err());
+
+    // Red Blob Games' flat-topped hex_to_pixel() with size
+    // 1/2 (unit width) and origin 0: x = 3/2 q size, y =
+    // sqrt(3) (q / 2 + r) size, with y down.
+    let (q, r) = (1.0f64, -2.0f64);
+    let size = 0.5;
+    let px = size * 1.5 * q;
+    let py = size * 3.0f64.sqrt() * (q / 2.0 + r);
+    let (x, y) = cube.cartesian_center::<f64>();
+    assert!((x - px).abs() < 1e-12);
+    assert!((y + py).abs() < 1e-12);
+    assert!((x - 0.75).abs() < 1e-12);
+    assert!((y - 0.75 * 3.0f64.sqrt()).abs() < 1e-12);
+}
+
+#[test]
+fn test_to_axial_checked() {
+    let hex = HexCoord::new(3i32, -2i32);
+    let cube = HexCubeCoord::from(hex);
+    assert_eq!(Ok(hex), cube.to_axial_checked());
+    let broken = HexCubeCoord::new_unchecked(3i32, 1i32, 2i32);
+    let err = broken.to_axial_checked().unwrap_err();
+    assert_eq!(CubeInvariantError { x: 3, y: 1, z: 2 }, err);
+}
+
+#[test]
+fn test_canonicalize() {
+    let hex = HexCoord::new(3i32, -2i32);
+    let cube = HexCubeCoord::from(hex);
+    assert_eq!(cube, cube.canonicalize());
+    let (x, _, z) = cube.coords();
+    let broken = HexCubeCoord::new_unchecked(x, 17, z);
+    assert!(broken.to_axial_checked().is_err());
+    let fixed = broken.canonicalize();
+    assert_eq!(cube, fixed);
+    assert_eq!(Ok(hex), fixed.to_axial_checked());
+    assert_eq!(HexCoord::from(broken), HexCoord::from(fixed));
+}
+
+#[test]
+fn test_reflect() {
+    let axes = [Axis::Q, Axis::R, Axis::S];
+    let hex = HexCubeCoord::from(HexCoord::new(3i32, -1i32));
+    for &axis in &axes {
+        let image = hex.reflect(axis);
+        assert_eq!(hex, image.reflect(axis));
+        let (x, y, z) = image.coords();
+        assert_eq!(0, x + y + z);
+        for &d in &DIRECTIONS {
+            assert_eq!(d, d.reflect(axis).reflect(axis));
+            let n = hex.neighbor(d).reflect(axis);
+            assert_eq!(image.neighbor(d.reflect(axis)), n);
+        }
+    }
+    assert_eq!((3, 1, -4), hex.reflect(Axis::Q).coords());
+    use Direction::*;
+    assert_eq!(S, N.reflect(Axis::Q));
+    assert_eq!(NE, N.reflect(Axis::R));
+    assert_eq!(NW, SE.reflect(Axis::R));
+    assert_eq!(S, N.reflect(Axis::S).reflect(Axis::Q).reflect(Axis::R));
+}
+
+#[test]
+fn test_canonicalize_hash() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let hash = |c: HexCubeCoord<i32>| {
+        let mut h = DefaultHasher::new();
+        c.hash(&mut h);
+        h.finish()
+    };
+    let a = HexCubeCoord::new_unchecked(2i32, 5, -4);
+    let b = HexCubeCoord::new_unchecked(2i32, -9, -4);
+    assert_ne!(a, b);
+    let (a, b) = (a.canonicalize(), b.canonicalize());
+    assert_eq!(a, b);
+    assert_eq!(hash(a), hash(b));
+
+    let c = HexCubeCoord::new_unchecked(2i32, 0, -4).canonical();
+    assert_eq!(a, c);
+    assert_eq!(hash(a), hash(c));
+    assert_eq!(c, c.canonical());
+}
+
+#[test]
+fn test_midpoint() {
+    let center = HexCubeCoord::from(HexCoord::new(2i32, -1i32));
+    for &d in &DIRECTIONS {
+        let a = center.neighbor(d);
+        let b = center.neighbor(d.opposite());
+        assert_eq!(center, a.midpoint(b));
+    }
+
+    let far = center.neighbor(Direction::N).neighbor(Direction::N);
+    assert_eq!(center.neighbor(Direction::N), center.midpoint(far));
+
+    // Adjacent hexes: the midpoint is on their shared edge,
+    // and goes toward `self`.
+    for &d in &DIRECTIONS {
+        let n = center.neighbor(d);
+        assert_eq!(center, center.midpoint(n));
+        assert_eq!(n, n.midpoint(center));
+    }
+
+    // Off the axes, the tie still goes toward `self`.
+    use Direction::*;
+    let ne = center.neighbor(NE);
+    let far = ne.neighbor(NE).neighbor(N);
+    assert_eq!(ne, center.midpoint(far));
+    assert_eq!(ne.neighbor(N), far.midpoint(center));
+
+    // Along the shared edge, both hexes are equally near
+    // either end: the tie goes the same way in both orders.
+    let far = ne.neighbor(N);
+    let mid = center.midpoint(far);
+    assert_eq!(mid, far.midpoint(center));
+    assert!(mid == ne || mid == center.neighbor(N));
+}
+
 #[test]
 fn test_distance_cube() {
     let start = HexCubeCoord::new(0.0f32, 0.0f32, 0.0f32).unwrap();
@@ -418,16 +1791,157 @@ fn test_distance_cube() {
     assert_eq!(3.0f32, start.distance(end));
 }
 
+impl<U: Float> HexCubeCoord<U> {
+    /// Linear interpolation between `self` (at `t == 0`)
+    /// and `b` (at `t == 1`). The result generally lies
+    /// between hex centers; use `round()` to find the
+    /// containing hex. See `FractionalCube::lerp()`.
+    pub fn lerp(self, b: Self, t: U) -> Self {
+        let (x, y, z) = FractionalCube::from(self)
+            .lerp(FractionalCube::from(b), t)
+            .coords();
+        HexCubeCoord::new_unchecked(x, y, z)
+    }
+
+    /// Cube coordinate of the hex containing the
+    /// fractional cube coordinate `self`. See
+    /// `FractionalCube::round()` for details.
+    pub fn round<T: Num + NumCast>(self) -> HexCubeCoord<T> {
+        FractionalCube::<U>::from(self).round()
+    }
+}
+
+#[test]
+fn test_lerp_round_cube() {
+    let a = HexCubeCoord::new(0.0f64, 0.0, 0.0).unwrap();
+    let b = HexCubeCoord::new(2.0f64, -4.0, 2.0).unwrap();
+    let m = a.lerp(b, 0.5);
+    assert_eq!((1.0, -2.0, 1.0), m.coords());
+    assert_eq!(a, a.lerp(b, 0.0));
+    assert_eq!(b, a.lerp(b, 1.0));
+
+    let c = HexCubeCoord::new_unchecked(0.9f64, -0.4, -0.5);
+    let r: HexCubeCoord<i32> = c.round();
+    assert_eq!((1, 0, -1), r.coords());
+    let c = HexCubeCoord::new_unchecked(0.1f64, 0.6, -0.7);
+    let r: HexCubeCoord<i32> = c.round();
+    assert_eq!((0, 1, -1), r.coords());
+}
+
+// Since `r` increases north, the axial `r` axis runs along
+// the negative cube `z` axis: `(q, r)` is cube
+// `(q, r - q, -r)`. This makes each of the six neighbor
+// steps a permutation of `(1, -1, 0)` in cube space.
 impl<T: Num + Clone> From<HexCoord<T>> for HexCubeCoord<T> {
     fn from(c: HexCoord<T>) -> Self {
-        let cl = c.clone();
-        let y = num::zero::<T>() - cl.q - cl.r;
-        HexCubeCoord::new_unchecked(c.q, y, c.r)
+        let y = c.r.clone() - c.q.clone();
+        let z = num::zero::<T>() - c.r;
+        HexCubeCoord::new_unchecked(c.q, y, z)
     }
 }
 
 impl<T: Num> From<HexCubeCoord<T>> for HexCoord<T> {
     fn from(c: HexCubeCoord<T>) -> Self {
-        HexCoord::new(c.x, c.z)
+        HexCoord::new(c.x, num::zero::<T>() - c.z)
+    }
+}
+
+#[test]
+fn test_float_cube_round_trip() {
+    let values = [0.0, -0.0, 0.1, -2.5, 1.0 / 3.0, 1e-17, 7e15, -1e300];
+    for &q in &values {
+        for &r in &values {
+            let hex = HexCoord::new(q, r);
+            let back = HexCoord::from(HexCubeCoord::from(hex));
+            assert_eq!(hex, back);
+        }
+    }
+    // Off the plane by rounding, but still round-tripped.
+    let hex = HexCoord::new(1e17f64, 0.1);
+    let (x, y, z) = HexCubeCoord::from(hex).coords();
+    assert_ne!(0.0, x + y + z);
+    assert_eq!(hex, HexCoord::from(HexCubeCoord::from(hex)));
+}
+
+// Axial coordinates are a vector space, so hexes sum
+// componentwise: summing offsets gives the total offset.
+impl<T: Num> std::iter::Sum for HexCoord<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(HexCoord::new(T::zero(), T::zero()), |a, b| {
+            HexCoord::new(a.q + b.q, a.r + b.r)
+        })
+    }
+}
+
+#[test]
+fn test_hex_sum() {
+    use Direction::*;
+    let moves = [N, N, SE, SW, NE, NE, S];
+    let delta = |d: Direction| {
+        let (q, r) = DIRECTION_DELTAS[d as usize];
+        HexCoord::new(q as i32, r as i32)
+    };
+    let total: HexCoord<i32> = moves.iter().map(|&d| delta(d)).sum();
+    let origin = HexCoord::new(0, 0);
+    let end = moves.iter().fold(origin, |h, &d| h.neighbor(d));
+    assert_eq!(end, total);
+    let none: HexCoord<i32> = std::iter::empty().sum();
+    assert_eq!(origin, none);
+}
+
+// The sum of two points on the cube plane is also on the
+// plane, so no check is needed.
+impl<T: Num> std::ops::Add for HexCubeCoord<T> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        HexCubeCoord::new_unchecked(
+            self.x + other.x,
+            self.y + other.y,
+            self.z + other.z,
+        )
+    }
+}
+
+impl<T: Num + CheckedAdd> HexCubeCoord<T> {
+    /// The sum `self + other`, or `None` if any component
+    /// would overflow `T`.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        Some(HexCubeCoord::new_unchecked(
+            self.x.checked_add(&other.x)?,
+            self.y.checked_add(&other.y)?,
+            self.z.checked_add(&other.z)?,
+        ))
+    }
+}
+
+#[test]
+fn test_cube_add() {
+    let a = HexCubeCoord::new(2i32, -5, 3).unwrap();
+    let b = HexCubeCoord::new(-1i32, 4, -3).unwrap();
+    assert_eq!((1, -1, 0), (a + b).coords());
+    assert_eq!(Some(a + b), a.checked_add(b));
+
+    let big = HexCubeCoord::new(i16::MAX - 1, 0, 1 - i16::MAX).unwrap();
+    let one = HexCubeCoord::new(1i16, -1, 0).unwrap();
+    let sum = big.checked_add(one).unwrap();
+    assert_eq!((i16::MAX, -1, 1 - i16::MAX), sum.coords());
+    assert_eq!(None, sum.checked_add(one));
+    let down = HexCubeCoord::new(0i16, 1, -1).unwrap();
+    assert_eq!(None, big.checked_add(big));
+    assert!(big.checked_add(down).is_some());
+}
+
+#[test]
+fn test_distance_axial() {
+    use Direction::*;
+    let start = HexCoord::new(0i32, 0i32);
+    for &d in &[NE, N, NW, SW, S, SE] {
+        let cube = HexCubeCoord::from(start.neighbor(d));
+        let (x, y, z) = cube.coords();
+        assert_eq!(0, x + y + z);
+        assert_eq!(1, start.distance(start.neighbor(d)));
     }
+    let end = start.neighbor(NE).neighbor(NE).neighbor(N);
+    assert_eq!(3, start.distance(end));
+    assert_eq!(3, end.distance(start));
 }
@@ -0,0 +1,602 @@
+//! Straight lines of hexes.
+
+use num::{PrimInt, Signed, ToPrimitive};
+
+use crate::*;
+
+// Fractional cube coordinate of `h`, nudged slightly off
+// the hex center so that line samples falling exactly on
+// an edge round consistently.
+fn nudged_cube<T>(h: HexCoord<T>) -> HexCubeCoord<f64>
+where
+    T: Num + ToPrimitive + Copy,
+{
+    let (x, y, z) = HexCubeCoord::from(h).coords();
+    let f = |c: T| c.to_f64().expect("coordinate not representable");
+    HexCubeCoord::new_unchecked(f(x) + 1e-6, f(y) + 2e-6, f(z) - 3e-6)
+}
+
+impl<T: PrimInt + Signed> HexCoord<T> {
+    /// Hexes on the straight line from `self` to `b`,
+    /// inclusive of both: one hex per unit of distance,
+    /// found by rounding evenly spaced samples along the
+    /// segment between the two hex centers.
+    pub fn line_to(self, b: Self) -> Vec<HexCoord<T>> {
+        let n = self
+            .distance(b)
+            .to_usize()
+            .expect("line length out of range");
+        if n == 0 {
+            return vec![self];
+        }
+        let start = nudged_cube(self);
+        let end = nudged_cube(b);
+        (0..=n)
+            .map(|i| {
+                let t = i as f64 / n as f64;
+                start.lerp(end, t).round().into()
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_line_to() {
+    use Direction::*;
+    let start = HexCoord::new(0i32, 0i32);
+    assert_eq!(vec![start], start.line_to(start));
+
+    let mut end = start;
+    let mut expected = vec![start];
+    for _ in 0..4 {
+        end = end.neighbor(NE);
+        expected.push(end);
+    }
+    assert_eq!(expected, start.line_to(end));
+
+    let end = HexCoord::new(3, 5);
+    let line = start.line_to(end);
+    assert_eq!(start.distance(end) as usize + 1, line.len());
+    for pair in line.windows(2) {
+        assert_eq!(1, pair[0].distance(pair[1]));
+    }
+}
+
+// One cube component of an integer line sample: the exact
+// value `p / n`, plus an infinitesimal `c` standing in for
+// the nudge of `nudged_cube()`. Returns the rounded component
+// and a key ordering its rounding error, for choosing which
+// component to recompute.
+fn round_component<T>(p: T, n: T, c: T) -> (T, (T, T))
+where
+    T: PrimInt + Signed,
+{
+    let two = T::one() + T::one();
+    let mut r = div_floor(two * p + n, two * n);
+    // An exact half rounds the way the nudge pushes it.
+    if r * two * n == two * p + n && c.is_negative() {
+        r = r - T::one();
+    }
+    let d = r * n - p;
+    let key = if d.is_zero() {
+        (T::zero(), c.abs())
+    } else {
+        (d.abs(), -d.signum() * c)
+    };
+    (r, key)
+}
+
+impl<T: PrimInt + Signed> HexCoord<T> {
+    /// The hexes of `line_to()`, computed with integer
+    /// arithmetic only: each sample is an exact fraction,
+    /// rounded as the floating-point samples would be. This
+    /// suits targets without floating point, and avoids
+    /// float precision limits on huge coordinates, but
+    /// intermediate values reach about twice the coordinates
+    /// times the line length, so these must fit in `T`.
+    pub fn line_to_int(self, b: Self) -> Vec<HexCoord<T>> {
+        let n = self.distance(b);
+        if n.is_zero() {
+            return vec![self];
+        }
+        let two = T::one() + T::one();
+        let nudges = [T::one(), two, -(two + T::one())];
+        let (ax, ay, az) = HexCubeCoord::from(self).coords();
+        let (bx, by, bz) = HexCubeCoord::from(b).coords();
+        let (start, end) = ([ax, ay, az], [bx, by, bz]);
+        num::range_inclusive(T::zero(), n)
+            .map(|i| {
+                let mut c = [T::zero(); 3];
+                let mut keys = [(T::zero(), T::zero()); 3];
+                for k in 0..3 {
+                    let p = start[k] * n + (end[k] - start[k]) * i;
+                    let (r, key) = round_component(p, n, nudges[k]);
+                    c[k] = r;
+                    keys[k] = key;
+                }
+                let [x, y, z] = c;
+                let [dx, dy, dz] = keys;
+                // Same choice as `FractionalCube::round()`;
+                // only `x` and `z` are needed for the result.
+                let (x, z) = if dx > dy && dx > dz {
+                    (-y - z, z)
+                } else if dy > dz {
+                    (x, z)
+                } else {
+                    (x, -x - y)
+                };
+                HexCoord::new(x, -z)
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_line_to_int() {
+    // Only integer bounds are needed.
+    fn int_line<T: PrimInt + Signed>(
+        a: HexCoord<T>,
+        b: HexCoord<T>,
+    ) -> Vec<HexCoord<T>> {
+        a.line_to_int(b)
+    }
+    let origin = HexCoord::new(0i32, 0i32);
+    assert_eq!(vec![origin], int_line(origin, origin));
+    for a in shapes::range(origin, 3) {
+        for b in shapes::range(HexCoord::new(1, -2), 5) {
+            assert_eq!(a.line_to(b), int_line(a, b));
+        }
+    }
+    let far = HexCoord::new(-37i64, 112);
+    for b in shapes::ring(far, 40) {
+        assert_eq!(far.line_to(b), int_line(far, b));
+    }
+}
+
+/// The line from `a` to `b` continued `steps` hexes beyond
+/// each end: `steps` hexes leading up to `a`, then
+/// `a.line_to(b)`, then `steps` hexes past `b`, all sampled
+/// from the same straight line through the two centers.
+/// Empty if `a == b`, since there is then no line direction.
+pub fn extend_line<T>(
+    a: HexCoord<T>,
+    b: HexCoord<T>,
+    steps: usize,
+) -> Vec<HexCoord<T>>
+where
+    T: PrimInt + Signed,
+{
+    let n = a.distance(b).to_usize().expect("line length out of range");
+    if n == 0 {
+        return Vec::new();
+    }
+    let start = nudged_cube(a);
+    let end = nudged_cube(b);
+    (0..=n + 2 * steps)
+        .map(|i| {
+            let t = (i as f64 - steps as f64) / n as f64;
+            start.lerp(end, t).round().into()
+        })
+        .collect()
+}
+
+#[test]
+fn test_extend_line() {
+    let h = HexCoord::new;
+    assert!(extend_line(h(1i32, 1i32), h(1, 1), 3).is_empty());
+    for &(a, b) in &[(h(0i32, 0i32), h(3, 1)), (h(2, -1), h(-1, 3))] {
+        let steps = 4;
+        let line = a.line_to(b);
+        let extended = extend_line(a, b, steps);
+        assert_eq!(line.len() + 2 * steps, extended.len());
+        assert_eq!(&line[..], &extended[steps..steps + line.len()]);
+        for pair in extended.windows(2) {
+            assert_eq!(1, pair[0].distance(pair[1]));
+        }
+        let first = extended[0];
+        let last = *extended.last().unwrap();
+        assert_eq!(steps as i32, first.distance(a));
+        assert_eq!(steps as i32, last.distance(b));
+    }
+    let straight = extend_line(h(0i32, 0i32), h(0, 1), 2);
+    assert_eq!(
+        vec![h(0, -2), h(0, -1), h(0, 0), h(0, 1), h(0, 2), h(0, 3)],
+        straight
+    );
+}
+
+/// True if the centers of `a`, `b` and `c` lie on a common
+/// straight line, whether along one of the three hex axes or
+/// not. Coincident hexes are trivially collinear.
+///
+/// Axial coordinates are a linear image of the Cartesian
+/// plane, so this is just a zero test of the 2D cross
+/// product of `b - a` and `c - a`.
+pub fn are_collinear<T>(
+    a: HexCoord<T>,
+    b: HexCoord<T>,
+    c: HexCoord<T>,
+) -> bool
+where
+    T: Num + Copy,
+{
+    let (dq1, dr1) = (b.q - a.q, b.r - a.r);
+    let (dq2, dr2) = (c.q - a.q, c.r - a.r);
+    dq1 * dr2 == dr1 * dq2
+}
+
+#[test]
+fn test_are_collinear() {
+    use Direction::*;
+    let h = HexCoord::new;
+    let a = h(2i32, -1i32);
+    let b = a.neighbor(N).neighbor(N);
+    let c = b.neighbor(N).neighbor(N).neighbor(N);
+    assert!(are_collinear(a, b, c));
+    assert!(are_collinear(c, a, b));
+    assert!(are_collinear(a.neighbor(SE), a, a.neighbor(NW)));
+    assert!(!are_collinear(a, b, b.neighbor(NE)));
+    assert!(!are_collinear(a, a.neighbor(NE), a.neighbor(N)));
+    // Off-axis, but still straight.
+    assert!(are_collinear(h(0, 0), h(1, 2), h(3, 6)));
+    assert!(!are_collinear(h(0, 0), h(1, 2), h(3, 5)));
+    assert!(are_collinear(a, a, c));
+    assert!(are_collinear(a, c, c));
+    assert!(are_collinear(a, a, a));
+}
+
+/// The three straight lines of hexes through `hex` along
+/// the hex axes, each extending `radius` hexes to either
+/// side of it: the line of constant `r` running SE, the
+/// line of constant `q` running N, and the line of constant
+/// cube `y` (the third, `s`, axis) running NE. Each line
+/// starts `radius` steps behind `hex`, so `hex` is in the
+/// middle. A negative radius gives empty lines.
+pub fn axis_lines<T>(
+    hex: HexCoord<T>,
+    radius: T,
+) -> [Vec<HexCoord<T>>; 3]
+where
+    T: PrimInt + Signed,
+{
+    let line = |d: Direction| -> Vec<HexCoord<T>> {
+        if radius.is_negative() {
+            return Vec::new();
+        }
+        num::range_inclusive(-radius, radius)
+            .map(|k| hex.step(d, k))
+            .collect()
+    };
+    [line(Direction::SE), line(Direction::N), line(Direction::NE)]
+}
+
+#[test]
+fn test_axis_lines() {
+    let hex = HexCoord::new(-2i32, 5i32);
+    let radius = 3;
+    let lines = axis_lines(hex, radius);
+    for line in &lines {
+        assert_eq!(2 * radius as usize + 1, line.len());
+        assert_eq!(hex, line[radius as usize]);
+        let (first, last) = (line[0], line[line.len() - 1]);
+        assert!(line.iter().all(|&h| are_collinear(first, last, h)));
+        assert_eq!(*line, first.line_to(last));
+    }
+    assert!(lines[0].iter().all(|h| h.r == hex.r));
+    assert!(lines[1].iter().all(|h| h.q == hex.q));
+    let y = |h: &HexCoord<i32>| HexCubeCoord::from(*h).y();
+    assert!(lines[2].iter().all(|h| y(h) == y(&hex)));
+    assert_eq!([vec![hex], vec![hex], vec![hex]], axis_lines(hex, 0));
+    assert!(axis_lines(hex, -1).iter().all(|l| l.is_empty()));
+}
+
+/// Step distance from `hex` to the nearest hex of
+/// `a.line_to(b)`. This is a discrete measure, to the
+/// nearest hex on the line, rather than a continuous
+/// distance to the segment between the centers.
+pub fn distance_to_line<T>(
+    hex: HexCoord<T>,
+    a: HexCoord<T>,
+    b: HexCoord<T>,
+) -> usize
+where
+    T: PrimInt + Signed,
+{
+    a.line_to(b)
+        .into_iter()
+        .map(|h| hex.step_distance(h))
+        .min()
+        .unwrap()
+}
+
+#[test]
+fn test_distance_to_line() {
+    use Direction::*;
+    let a = HexCoord::new(0i32, 0i32);
+    let b = HexCoord::new(0, 6);
+    let mid = HexCoord::new(0, 3);
+    assert_eq!(0, distance_to_line(mid, a, b));
+    assert_eq!(1, distance_to_line(mid.neighbor(SE), a, b));
+    assert_eq!(
+        2,
+        distance_to_line(mid.neighbor(NW).neighbor(SW), a, b)
+    );
+    // Beyond the end, the nearest line hex is the endpoint.
+    assert_eq!(3, distance_to_line(HexCoord::new(0, 9), a, b));
+    assert_eq!(2, distance_to_line(HexCoord::new(2, 1), a, a));
+}
+
+/// The hex containing the mirror image of the center of
+/// `hex` in the straight line through the centers of `a`
+/// and `b`, as for a projectile bouncing off a wall. When
+/// the line runs along a hex axis, or halfway between two,
+/// the image is exactly a hex center and reflecting twice
+/// gives back `hex`; in other directions the image is
+/// rounded to the hex containing it.
+///
+/// # Panics
+///
+/// Panics if `a == b`.
+pub fn reflect_across_line<T>(
+    hex: HexCoord<T>,
+    a: HexCoord<T>,
+    b: HexCoord<T>,
+) -> HexCoord<T>
+where
+    T: PrimInt + Signed,
+{
+    assert!(a != b, "reflection line needs two distinct hexes");
+    let center = |h: HexCoord<T>| {
+        let f =
+            |c: T| c.to_f64().expect("coordinate not representable");
+        HexCoord::new(f(h.q), f(h.r)).cartesian_center::<f64>()
+    };
+    let (p, p0, p1) = (center(hex), center(a), center(b));
+    let (dx, dy) = (p1.0 - p0.0, p1.1 - p0.1);
+    let t =
+        ((p.0 - p0.0) * dx + (p.1 - p0.1) * dy) / (dx * dx + dy * dy);
+    let foot = (p0.0 + t * dx, p0.1 + t * dy);
+    HexCoord::from_cartesian((2.0 * foot.0 - p.0, 2.0 * foot.1 - p.1))
+}
+
+#[test]
+fn test_reflect_across_line() {
+    use Direction::*;
+    let a = HexCoord::new(1i32, -2i32);
+    for &d in &DIRECTIONS {
+        let b = a.step(d, 2);
+        // Hexes on the line stay put.
+        for k in -3..5 {
+            let on = a.step(d, k);
+            assert_eq!(on, reflect_across_line(on, a, b));
+        }
+        // Along an axis, the reflection is exact and mirrors
+        // the neighbors of `a` in pairs.
+        for n in a.neighbors() {
+            let image = reflect_across_line(n, a, b);
+            assert_eq!(1, a.distance(image));
+            assert_eq!(n, reflect_across_line(image, a, b));
+        }
+        assert_eq!(
+            a.neighbor(d.rotate(1)),
+            reflect_across_line(a.neighbor(d.rotate(-1)), a, b)
+        );
+    }
+
+    // Halfway between the N and NE axes.
+    let b = a.neighbor(N).neighbor(NE);
+    for hex in shapes::range(a, 3) {
+        let image = reflect_across_line(hex, a, b);
+        assert_eq!(a.distance(hex), a.distance(image));
+        assert_eq!(hex, reflect_across_line(image, a, b));
+    }
+}
+
+/// First hex shared by the line from `a0` to `a1` and the
+/// line from `b0` to `b1`, in order along the first line,
+/// or `None` if the lines have no hex in common. Collinear
+/// overlapping lines thus give the first overlapping hex
+/// reached from `a0`.
+pub fn line_intersection<T>(
+    a0: HexCoord<T>,
+    a1: HexCoord<T>,
+    b0: HexCoord<T>,
+    b1: HexCoord<T>,
+) -> Option<HexCoord<T>>
+where
+    T: PrimInt + Signed,
+{
+    let line_b = b0.line_to(b1);
+    a0.line_to(a1).into_iter().find(|h| line_b.contains(h))
+}
+
+#[test]
+fn test_line_intersection() {
+    let h = HexCoord::new;
+
+    let crossing =
+        line_intersection(h(-2, 0), h(2, 0), h(0, -2), h(0, 2));
+    assert_eq!(Some(h(0, 0)), crossing);
+
+    let parallel =
+        line_intersection(h(0, 0), h(3, 0), h(0, 1), h(3, 1));
+    assert_eq!(None, parallel);
+    let disjoint =
+        line_intersection(h(0, 0), h(3, 0), h(5, 0), h(7, 0));
+    assert_eq!(None, disjoint);
+
+    let overlap = line_intersection(h(0, 0), h(4, 0), h(2, 0), h(6, 0));
+    assert_eq!(Some(h(2, 0)), overlap);
+    let overlap = line_intersection(h(0, 0), h(4, 0), h(6, 0), h(2, 0));
+    assert_eq!(Some(h(2, 0)), overlap);
+    let overlap = line_intersection(h(4, 0), h(0, 0), h(2, 0), h(6, 0));
+    assert_eq!(Some(h(4, 0)), overlap);
+}
+
+// Cartesian corners of `h`, computed in `f64`.
+fn corners_f64<T>(h: HexCoord<T>) -> [(f64, f64); 6]
+where
+    T: ToPrimitive,
+{
+    let f = |c: T| c.to_f64().expect("coordinate not representable");
+    HexCoord::new(f(h.q), f(h.r)).cartesian_corners()
+}
+
+// True if the segment from `a` to `b` touches the convex,
+// counterclockwise polygon `poly`, within a small tolerance.
+// The segment is clipped against each edge's half-plane in
+// turn (Cyrus-Beck).
+fn segment_touches(
+    a: (f64, f64),
+    b: (f64, f64),
+    poly: &[(f64, f64)],
+) -> bool {
+    const EPS: f64 = 1e-9;
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+    let n = poly.len();
+    for i in 0..n {
+        let (px, py) = poly[i];
+        let (qx, qy) = poly[(i + 1) % n];
+        // Outward normal of a counterclockwise edge.
+        let (nx, ny) = (qy - py, px - qx);
+        let num = nx * (a.0 - px) + ny * (a.1 - py);
+        let den = nx * (b.0 - a.0) + ny * (b.1 - a.1);
+        if den.abs() < EPS {
+            if num > EPS {
+                return false;
+            }
+        } else {
+            let t = (EPS - num) / den;
+            if den > 0.0 {
+                t1 = t1.min(t);
+            } else {
+                t0 = t0.max(t);
+            }
+            if t0 > t1 {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+impl<T: PrimInt + Signed> HexCoord<T> {
+    /// Every hex touched by the straight segment between
+    /// the centers of `self` and `b`, however glancingly:
+    /// hexes whose corner is just clipped, and both hexes
+    /// when the segment runs exactly along an edge or
+    /// through a corner. This is always a superset of
+    /// `line_to()`, but is not in general a contiguous
+    /// path of single steps. Hexes are listed in order of
+    /// the projection of their centers onto the segment.
+    pub fn line_supercover(self, b: Self) -> Vec<HexCoord<T>> {
+        let line = self.line_to(b);
+        if line.len() == 1 {
+            return line;
+        }
+        let center = |h: HexCoord<T>| {
+            let f = |c: T| {
+                c.to_f64().expect("coordinate not representable")
+            };
+            HexCoord::new(f(h.q), f(h.r)).cartesian_center::<f64>()
+        };
+        let (p0, p1) = (center(self), center(b));
+        let (dx, dy) = (p1.0 - p0.0, p1.1 - p0.1);
+        let along = |h: HexCoord<T>| {
+            let (x, y) = center(h);
+            ((x - p0.0) * dx + (y - p0.1) * dy) / (dx * dx + dy * dy)
+        };
+
+        let mut cover: Vec<HexCoord<T>> = Vec::new();
+        for &h in &line {
+            for c in std::iter::once(h).chain(h.neighbors()) {
+                if !cover.contains(&c)
+                    && segment_touches(p0, p1, &corners_f64(c))
+                {
+                    cover.push(c);
+                }
+            }
+        }
+        cover.sort_by(|&g, &h| {
+            along(g)
+                .partial_cmp(&along(h))
+                .unwrap()
+                .then_with(|| g.cmp(&h))
+        });
+        cover
+    }
+}
+
+#[test]
+fn test_line_supercover() {
+    let h = HexCoord::new;
+    let start = h(0i32, 0i32);
+    assert_eq!(vec![start], start.line_supercover(start));
+
+    // A straight run along one direction crosses only edge
+    // midpoints, so nothing extra is touched.
+    let straight = h(3, 3);
+    assert_eq!(
+        start.line_to(straight),
+        start.line_supercover(straight)
+    );
+
+    for &end in &[h(3, 1), h(-2, 3), h(5, -4), h(1, 4), h(-4, -1)] {
+        let line = start.line_to(end);
+        let cover = start.line_supercover(end);
+        assert!(line.iter().all(|h| cover.contains(h)));
+        assert_eq!(Some(&start), cover.first());
+        assert_eq!(Some(&end), cover.last());
+    }
+
+    // This segment cuts across a corner of `(2, 0)` that
+    // `line_to()` skips.
+    let end = h(4, 1);
+    let line = start.line_to(end);
+    let cover = start.line_supercover(end);
+    assert!(!line.contains(&h(2, 0)));
+    assert_eq!(line.len() + 1, cover.len());
+    assert!(cover.contains(&h(2, 0)));
+
+    // This one runs along the edge shared by `(0, 1)` and
+    // `(1, 1)`, so both are touched.
+    let cover = start.line_supercover(h(1, 2));
+    assert_eq!(vec![start, h(0, 1), h(1, 1), h(1, 2)], cover);
+}
+
+/// Iterator over the hexes reached by stepping repeatedly
+/// from `start` in direction `d`, beginning with
+/// `start.neighbor(d)`. The ray is unbounded, so use
+/// `take()` or `take_while()` to limit it; it ends only if a
+/// coordinate would overflow `T`.
+pub fn ray<T>(
+    start: HexCoord<T>,
+    d: Direction,
+) -> impl Iterator<Item = HexCoord<T>>
+where
+    T: Num + Copy + CheckedAdd + CheckedSub,
+{
+    std::iter::successors(start.checked_neighbor(d), move |&h| {
+        h.checked_neighbor(d)
+    })
+}
+
+#[test]
+fn test_ray() {
+    let start = HexCoord::new(2i32, -1i32);
+    for &d in &DIRECTIONS {
+        let hexes: Vec<_> = ray(start, d).take(5).collect();
+        assert_eq!(5, hexes.len());
+        let mut prev = start;
+        for (i, &h) in hexes.iter().enumerate() {
+            assert_eq!(prev.neighbor(d), h);
+            assert_eq!(i as i32 + 1, start.distance(h));
+            prev = h;
+        }
+    }
+
+    let near_edge = HexCoord::new(120i8, 0i8);
+    let hexes: Vec<_> = ray(near_edge, Direction::SE).collect();
+    assert_eq!(7, hexes.len());
+    assert_eq!(Some(&HexCoord::new(i8::MAX, 0)), hexes.last());
+}
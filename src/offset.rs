@@ -0,0 +1,79 @@
+use num::{PrimInt, Signed};
+
+use crate::*;
+
+/// Which columns of an offset grid are shifted. Flat-topped
+/// hexes stack into columns, and alternate columns sit half
+/// a hex further south than their neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OffsetKind {
+    /// Odd columns are shifted south ("odd-q").
+    OddQ,
+    /// Even columns are shifted south ("even-q").
+    EvenQ,
+}
+
+/// Hex grid location in offset coordinates, as used by
+/// tilemap tools that index a board by `(col, row)`.
+/// Columns increase east and rows increase south, so that
+/// row `0` is the top of the board. The `OffsetKind` is
+/// needed to interpret the coordinate.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct OffsetCoord<T> {
+    pub col: T,
+    pub row: T,
+}
+
+impl<T: PrimInt + Signed> OffsetCoord<T> {
+    /// Make an offset coordinate.
+    pub fn new(col: T, row: T) -> Self {
+        OffsetCoord { col, row }
+    }
+
+    // Half the column, rounded toward the shifted side.
+    fn shift(col: T, kind: OffsetKind) -> T {
+        let two = T::one() + T::one();
+        let parity = col & T::one();
+        match kind {
+            OffsetKind::OddQ => (col - parity) / two,
+            OffsetKind::EvenQ => (col + parity) / two,
+        }
+    }
+
+    /// Offset coordinate of `hex`.
+    pub fn from_hex(hex: HexCoord<T>, kind: OffsetKind) -> Self {
+        OffsetCoord::new(hex.q, Self::shift(hex.q, kind) - hex.r)
+    }
+
+    /// Axial coordinate of `self`.
+    pub fn to_hex(self, kind: OffsetKind) -> HexCoord<T> {
+        HexCoord::new(self.col, Self::shift(self.col, kind) - self.row)
+    }
+}
+
+#[test]
+fn test_offset() {
+    use Direction::*;
+    for &kind in &[OffsetKind::OddQ, OffsetKind::EvenQ] {
+        for q in -4i32..=4 {
+            for r in -4i32..=4 {
+                let hex = HexCoord::new(q, r);
+                let off = OffsetCoord::from_hex(hex, kind);
+                assert_eq!(hex, off.to_hex(kind));
+                let south = OffsetCoord::new(off.col, off.row + 1);
+                assert_eq!(hex.neighbor(S), south.to_hex(kind));
+            }
+        }
+    }
+
+    let origin = OffsetCoord::new(0i32, 0i32);
+    let odd = OffsetCoord::new(1i32, 0i32);
+    assert_eq!(HexCoord::new(0, 0), origin.to_hex(OffsetKind::OddQ));
+    assert_eq!(HexCoord::new(0, 0), origin.to_hex(OffsetKind::EvenQ));
+    let h = odd.to_hex(OffsetKind::OddQ);
+    assert_eq!(origin.to_hex(OffsetKind::OddQ).neighbor(SE), h);
+    let h = odd.to_hex(OffsetKind::EvenQ);
+    assert_eq!(origin.to_hex(OffsetKind::EvenQ).neighbor(NE), h);
+}
@@ -0,0 +1,934 @@
+//! Paths: sequences of hexes, each normally adjacent to the
+//! next.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::convert::TryFrom;
+use std::hash::Hash;
+
+use num::{PrimInt, Signed};
+
+use thiserror::Error;
+
+use crate::*;
+
+/// True if each hex of `path` is a neighbor of the next.
+/// Empty and single-hex paths are trivially contiguous.
+pub fn is_contiguous<T>(path: &[HexCoord<T>]) -> bool
+where
+    T: Num + PartialOrd + Clone,
+{
+    path.windows(2)
+        .all(|w| w[0].clone().distance(w[1].clone()) == T::one())
+}
+
+/// The hexes of `path` in reverse order. Adjacency is
+/// symmetric, so the result is contiguous exactly when
+/// `path` is.
+pub fn reverse_path<T: Clone>(
+    path: &[HexCoord<T>],
+) -> Vec<HexCoord<T>> {
+    path.iter().rev().cloned().collect()
+}
+
+#[test]
+fn test_is_contiguous() {
+    let start = HexCoord::new(0i32, 0i32);
+    let path = start.line_to(HexCoord::new(4, -3));
+    assert!(is_contiguous(&path));
+    assert!(is_contiguous(&path[..1]));
+    assert!(is_contiguous::<i32>(&[]));
+
+    let reversed = reverse_path(&path);
+    assert!(is_contiguous(&reversed));
+    assert_eq!(path.first(), reversed.last());
+    assert_eq!(path, reverse_path(&reversed));
+
+    let mut gapped = path.clone();
+    gapped.remove(2);
+    assert!(!is_contiguous(&gapped));
+    assert!(!is_contiguous(&reverse_path(&gapped)));
+
+    assert!(!is_contiguous(&[start, start]));
+}
+
+/// Error indicating that a path is not contiguous: the hex
+/// at the given index is not a neighbor of the one after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+#[error("path not contiguous after step {}", .0)]
+pub struct NonAdjacentError(pub usize);
+
+/// The direction of each step along `path`, so that
+/// `path[i].neighbor(dirns[i]) == path[i + 1]`. Empty and
+/// single-hex paths give no directions.
+pub fn path_directions<T>(
+    path: &[HexCoord<T>],
+) -> Result<Vec<Direction>, NonAdjacentError>
+where
+    T: Num + Copy,
+{
+    path.windows(2)
+        .enumerate()
+        .map(|(i, w)| {
+            w[0].direction_to(w[1]).ok_or(NonAdjacentError(i))
+        })
+        .collect()
+}
+
+#[test]
+fn test_path_directions() {
+    use Direction::*;
+    let h = HexCoord::new;
+    let path = [h(0i32, 0i32), h(1, 1), h(1, 2), h(0, 2), h(0, 1)];
+    assert_eq!(Ok(vec![NE, N, NW, S]), path_directions(&path));
+    assert_eq!(Ok(vec![]), path_directions(&path[..1]));
+    assert_eq!(Ok(vec![]), path_directions::<i32>(&[]));
+
+    let gapped = [h(0, 0), h(1, 1), h(2, 3), h(2, 4)];
+    let err = path_directions(&gapped).unwrap_err();
+    assert_eq!(NonAdjacentError(1), err);
+    assert_eq!("path not contiguous after step 1", err.to_string());
+}
+
+/// Orientation of a closed loop in the Cartesian plane of
+/// `HexCoord::cartesian_center()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Winding {
+    /// Clockwise
+    Clockwise,
+    /// Counterclockwise
+    CounterClockwise,
+}
+
+/// Orientation of the closed loop through the centers of
+/// `loop_hexes`, in order and back to the first, by the sign
+/// of its shoelace area. `None` if the loop encloses no
+/// area, as for fewer than three hexes or collinear ones.
+///
+/// The area is computed exactly from the axial coordinates:
+/// the axial-to-Cartesian map has positive determinant, so
+/// it preserves orientation.
+pub fn loop_winding<T>(loop_hexes: &[HexCoord<T>]) -> Option<Winding>
+where
+    T: Num + Copy + PartialOrd,
+{
+    let n = loop_hexes.len();
+    let mut area = T::zero();
+    for i in 0..n {
+        let (a, b) = (loop_hexes[i], loop_hexes[(i + 1) % n]);
+        area = area + (a.q * b.r - b.q * a.r);
+    }
+    if area > T::zero() {
+        Some(Winding::CounterClockwise)
+    } else if area < T::zero() {
+        Some(Winding::Clockwise)
+    } else {
+        None
+    }
+}
+
+#[test]
+fn test_loop_winding() {
+    let center = HexCoord::new(5i32, -2i32);
+    let ring: Vec<_> = shapes::ring(center, 2).collect();
+    assert_eq!(Some(Winding::CounterClockwise), loop_winding(&ring));
+    let reversed = reverse_path(&ring);
+    assert_eq!(Some(Winding::Clockwise), loop_winding(&reversed));
+
+    // Centers along an axis are collinear, but those of a
+    // general `line_to()` zigzag, and so enclose some area.
+    let line = center.line_to(HexCoord::new(9, 2));
+    assert_eq!(None, loop_winding(&line));
+    // Alternating steps of `(1, 1)` and `(1, 0)`: the middle
+    // centers sit north of the chord, so closing the loop runs
+    // clockwise.
+    let zigzag = center.line_to(HexCoord::new(9, 0));
+    assert_eq!(HexCoord::new(6, -1), zigzag[1]);
+    assert_eq!(HexCoord::new(7, -1), zigzag[2]);
+    assert_eq!(Some(Winding::Clockwise), loop_winding(&zigzag));
+    let back = reverse_path(&zigzag);
+    assert_eq!(Some(Winding::CounterClockwise), loop_winding(&back));
+    assert_eq!(None, loop_winding(&ring[..2]));
+    assert_eq!(None, loop_winding::<i32>(&[]));
+}
+
+/// Step distance from each hex of `region` to the nearest
+/// hex of `sources`, moving only between neighboring hexes
+/// of `region`. Sources outside `region` are ignored, and
+/// hexes of `region` that cannot be reached from any source
+/// are absent from the result.
+pub fn distance_field<T>(
+    sources: &HexSet<T>,
+    region: &HexSet<T>,
+) -> HexMap<T, usize>
+where
+    T: Num + Copy + Hash + Eq,
+{
+    let mut field = HexMap::new();
+    let mut queue = VecDeque::new();
+    for &s in sources.iter() {
+        if region.contains(s) {
+            field.insert(s, 0);
+            queue.push_back(s);
+        }
+    }
+    while let Some(hex) = queue.pop_front() {
+        let d = *field.get(hex).unwrap();
+        for n in hex.neighbors() {
+            if region.contains(n) && !field.contains(n) {
+                field.insert(n, d + 1);
+                queue.push_back(n);
+            }
+        }
+    }
+    field
+}
+
+#[test]
+fn test_distance_field() {
+    use Direction::*;
+    let h = HexCoord::new;
+    let center = h(0i32, 0i32);
+    let mut region: HexSet<i32> = shapes::range(center, 3).collect();
+
+    // Wall off one hex outside the disk.
+    let walled = h(10, 0);
+    region.insert(walled);
+
+    let a = center.neighbor(NE).neighbor(NE);
+    let b = center.neighbor(SW).neighbor(S);
+    let sources: HexSet<i32> =
+        vec![a, b, h(20, 20)].into_iter().collect();
+    let field = distance_field(&sources, &region);
+
+    assert_eq!(region.len() - 1, field.len());
+    assert!(!field.contains(walled));
+    assert!(!field.contains(h(20, 20)));
+    assert_eq!(Some(&0), field.get(a));
+    assert_eq!(Some(&0), field.get(b));
+    for &hex in region.iter().filter(|&&hex| hex != walled) {
+        let d = a.step_distance(hex).min(b.step_distance(hex));
+        assert_eq!(Some(&d), field.get(hex));
+    }
+}
+
+/// Pixel positions of the centers of the hexes of `path`
+/// placed by `layout`, in order: a polyline for drawing the
+/// path.
+pub fn path_polyline<T, U>(
+    path: &[HexCoord<T>],
+    layout: &Layout<U>,
+) -> Vec<(U, U)>
+where
+    T: Num + Clone + Into<U>,
+    U: Float,
+{
+    path.iter()
+        .map(|hex| layout.hex_to_pixel(hex.clone()))
+        .collect()
+}
+
+#[test]
+fn test_path_polyline() {
+    let path = HexCoord::new(-2i32, 1i32).line_to(HexCoord::new(3, -2));
+    let unit = Layout::default();
+    let points = path_polyline::<_, f64>(&path, &unit);
+    assert_eq!(path.len(), points.len());
+    // Neighboring centers are an inradius apart, twice over.
+    let step = 2.0 * cartesian::inradius::<f64>();
+    for w in points.windows(2) {
+        let (dx, dy) = (w[1].0 - w[0].0, w[1].1 - w[0].1);
+        assert!(((dx * dx + dy * dy).sqrt() - step).abs() < 1e-12);
+    }
+    let layout = Layout::new((10.0, 10.0), (3.0, 4.0));
+    let points = path_polyline(&path, &layout);
+    assert_eq!(layout.hex_to_pixel(path[2]), points[2]);
+    assert!(path_polyline::<i32, f64>(&[], &unit).is_empty());
+}
+
+/// The hexes of `region` grouped by distance from the
+/// nearest of `sources`, for animating waves spreading from
+/// them: element `k` holds the hexes at distance exactly
+/// `k`, in increasing order. Distances are measured within
+/// `region` as by `distance_field()`, so where ripples from
+/// different sources meet, each hex appears once, in the
+/// frame of the nearer source.
+pub fn ripple_frames<T>(
+    sources: &[HexCoord<T>],
+    region: &HexSet<T>,
+) -> Vec<Vec<HexCoord<T>>>
+where
+    T: Num + Copy + Hash + Ord,
+{
+    let sources: HexSet<T> = sources.iter().copied().collect();
+    let field = distance_field(&sources, region);
+    let mut frames: Vec<Vec<HexCoord<T>>> = Vec::new();
+    for (&hex, &d) in field.iter() {
+        if frames.len() <= d {
+            frames.resize(d + 1, Vec::new());
+        }
+        frames[d].push(hex);
+    }
+    for frame in &mut frames {
+        frame.sort();
+    }
+    frames
+}
+
+#[test]
+fn test_ripple_frames() {
+    let h = HexCoord::new;
+    let region: HexSet<i32> = shapes::range(h(0i32, 0i32), 6).collect();
+    let (a, b) = (h(-2, 0), h(2, 0));
+    let frames = ripple_frames(&[a, b], &region);
+    assert_eq!(vec![a, b], frames[0]);
+    let total: usize = frames.iter().map(|f| f.len()).sum();
+    assert_eq!(region.len(), total);
+    let mut seen = HexSet::new();
+    for (k, frame) in frames.iter().enumerate() {
+        assert!(!frame.is_empty());
+        for &hex in frame {
+            let d = a.step_distance(hex).min(b.step_distance(hex));
+            assert_eq!(k, d);
+            assert!(seen.insert(hex));
+        }
+    }
+    // Equidistant from both sources: one entry, at distance 2.
+    assert!(frames[2].contains(&h(0, 0)));
+
+    assert!(ripple_frames(&[], &region).is_empty());
+    assert!(ripple_frames(&[h(20, 20)], &region).is_empty());
+}
+
+/// Step distances between all pairs of `hexes`: entry
+/// `[i][j]` is the step distance from `hexes[i]` to
+/// `hexes[j]`. The matrix is symmetric with a zero
+/// diagonal.
+pub fn distance_matrix<T>(hexes: &[HexCoord<T>]) -> Vec<Vec<usize>>
+where
+    T: Num + PartialOrd + Clone + ToPrimitive,
+{
+    hexes
+        .iter()
+        .map(|a| {
+            hexes
+                .iter()
+                .map(|b| a.clone().step_distance(b.clone()))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_distance_matrix() {
+    let h = HexCoord::new;
+    let hexes = [h(0i32, 0i32), h(3, 1), h(-2, 4), h(5, -5), h(3, 1)];
+    let m = distance_matrix(&hexes);
+    assert_eq!(hexes.len(), m.len());
+    for i in 0..hexes.len() {
+        assert_eq!(hexes.len(), m[i].len());
+        assert_eq!(0, m[i][i]);
+        for j in 0..hexes.len() {
+            assert_eq!(m[i][j], m[j][i]);
+            assert_eq!(hexes[i].step_distance(hexes[j]), m[i][j]);
+        }
+    }
+    assert_eq!(3, m[0][1]);
+    assert_eq!(0, m[1][4]);
+    assert!(distance_matrix::<i32>(&[]).is_empty());
+}
+
+/// Movement costs over the grid, for the cost-aware
+/// searches `reachable_in_field()`, `dijkstra_in_field()`
+/// and `astar_in_field()`. Any closure from a hex to an
+/// `Option<u32>` entry cost is a `HexField`.
+pub trait HexField<T> {
+    /// Cost of entering `hex`, or `None` if it cannot be
+    /// entered.
+    fn cost(&self, hex: HexCoord<T>) -> Option<u32>;
+
+    /// True if `hex` can be entered at all. Defaults to
+    /// whether `cost()` is `Some`.
+    fn passable(&self, hex: HexCoord<T>) -> bool {
+        self.cost(hex).is_some()
+    }
+
+    /// `cost()` of `hex` if it is `passable()`, else `None`:
+    /// what the search asks of the field. The default
+    /// consults both; a field where `cost()` alone decides
+    /// passability can override this to evaluate it once.
+    fn entry_cost(&self, hex: HexCoord<T>) -> Option<u32>
+    where
+        T: Clone,
+    {
+        if self.passable(hex.clone()) {
+            self.cost(hex)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T, F> HexField<T> for F
+where
+    F: Fn(HexCoord<T>) -> Option<u32>,
+{
+    fn cost(&self, hex: HexCoord<T>) -> Option<u32> {
+        self(hex)
+    }
+
+    fn entry_cost(&self, hex: HexCoord<T>) -> Option<u32> {
+        self(hex)
+    }
+}
+
+/// Least total cost of reaching each hex from `start` in at
+/// most `max_steps` steps without the total exceeding
+/// `budget`. Entering a hex costs `cost` of that hex;
+/// `None` marks the hex impassable. The start hex costs
+/// nothing and is always present. Because of the step cap,
+/// the cheapest route to a hex may be too long to use, in
+/// which case its cost is that of the cheapest route short
+/// enough.
+pub fn reachable_budgeted<T, F>(
+    start: HexCoord<T>,
+    max_steps: usize,
+    budget: u32,
+    cost: F,
+) -> HexMap<T, u32>
+where
+    T: Num + Copy + Hash + Eq,
+    F: Fn(HexCoord<T>) -> Option<u32>,
+{
+    reachable_in_field(start, max_steps, budget, &cost)
+}
+
+/// As `reachable_budgeted()`, with entry costs given by
+/// `field`. Hexes that are not `passable()` are never
+/// entered.
+pub fn reachable_in_field<T>(
+    start: HexCoord<T>,
+    max_steps: usize,
+    budget: u32,
+    field: &impl HexField<T>,
+) -> HexMap<T, u32>
+where
+    T: Num + Copy + Hash + Eq,
+{
+    let mut best = HexMap::new();
+    best.insert(start, 0);
+    // Hexes whose cost improved in the last round, with the
+    // improved cost: after round `k`, `best` holds the least
+    // cost over routes of at most `k` steps.
+    let mut frontier = vec![(start, 0u32)];
+    for _ in 0..max_steps {
+        let mut next: HexMap<T, u32> = HexMap::new();
+        for &(hex, c) in &frontier {
+            for n in hex.neighbors() {
+                let total =
+                    field.entry_cost(n).and_then(|e| c.checked_add(e));
+                let total = match total {
+                    Some(total) if total <= budget => total,
+                    _ => continue,
+                };
+                if !matches!(best.get(n), Some(&b) if b <= total) {
+                    best.insert(n, total);
+                    next.insert(n, total);
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        frontier = next.iter().map(|(&h, &c)| (h, c)).collect();
+    }
+    best
+}
+
+#[test]
+fn test_reachable_budgeted() {
+    let start = HexCoord::new(0i32, 0i32);
+
+    // Uniform cost: whichever limit is tighter wins.
+    let flat = |_| Some(1);
+    let steps_bound = reachable_budgeted(start, 2, 100, flat);
+    assert_eq!(19, steps_bound.len());
+    let budget_bound = reachable_budgeted(start, 100, 2, flat);
+    assert_eq!(19, budget_bound.len());
+    for (&hex, &c) in budget_bound.iter() {
+        assert_eq!(c, start.step_distance(hex) as u32);
+    }
+
+    // A costly hex north of the start, and a wall to the
+    // south: two steps to the north must pay for the costly
+    // hex, but three steps can go around it.
+    let costly = HexCoord::new(0, 1);
+    let wall = HexCoord::new(0, -1);
+    let terrain = |hex| match hex {
+        h if h == costly => Some(5),
+        h if h == wall => None,
+        _ => Some(1),
+    };
+    let target = HexCoord::new(0, 2);
+    let short = reachable_budgeted(start, 2, 100, terrain);
+    assert_eq!(Some(&6), short.get(target));
+    assert_eq!(Some(&5), short.get(costly));
+    assert!(!short.contains(wall));
+    let long = reachable_budgeted(start, 3, 100, terrain);
+    assert_eq!(Some(&3), long.get(target));
+    let poor = reachable_budgeted(start, 3, 2, terrain);
+    assert!(!poor.contains(target));
+    assert!(!poor.contains(costly));
+    assert_eq!(Some(&0), poor.get(start));
+
+    // The cost closure is called once per neighbor examined.
+    let calls = std::cell::Cell::new(0);
+    let counted = |hex| {
+        calls.set(calls.get() + 1);
+        terrain(hex)
+    };
+    reachable_budgeted(start, 1, 100, counted);
+    assert_eq!(6, calls.get());
+}
+
+/// A least-cost path from `start` to `goal`, both
+/// included, with its total cost, where entering a hex costs
+/// `cost` of that hex and `None` marks it impassable. The
+/// start hex costs nothing. `None` if `goal` cannot be
+/// reached for at most `max_cost`. Hexes costing `0` must
+/// not form an unbounded region, or the search may not end.
+pub fn dijkstra<T, F>(
+    start: HexCoord<T>,
+    goal: HexCoord<T>,
+    max_cost: u32,
+    cost: F,
+) -> Option<(Vec<HexCoord<T>>, u32)>
+where
+    T: PrimInt + Signed + Hash,
+    F: Fn(HexCoord<T>) -> Option<u32>,
+{
+    dijkstra_in_field(start, goal, max_cost, &cost)
+}
+
+/// As `dijkstra()`, with entry costs given by `field`.
+pub fn dijkstra_in_field<T>(
+    start: HexCoord<T>,
+    goal: HexCoord<T>,
+    max_cost: u32,
+    field: &impl HexField<T>,
+) -> Option<(Vec<HexCoord<T>>, u32)>
+where
+    T: PrimInt + Signed + Hash,
+{
+    best_path(start, goal, max_cost, field, |_| 0)
+}
+
+/// As `dijkstra()`, but guided toward `goal` by step
+/// distance, so that typically far fewer hexes are
+/// searched. The path found is least-cost as long as every
+/// passable hex costs at least `1`; cheaper hexes make the
+/// guide overestimate, and the path may then cost more than
+/// necessary.
+pub fn astar<T, F>(
+    start: HexCoord<T>,
+    goal: HexCoord<T>,
+    max_cost: u32,
+    cost: F,
+) -> Option<(Vec<HexCoord<T>>, u32)>
+where
+    T: PrimInt + Signed + Hash,
+    F: Fn(HexCoord<T>) -> Option<u32>,
+{
+    astar_in_field(start, goal, max_cost, &cost)
+}
+
+/// As `astar()`, with entry costs given by `field`.
+pub fn astar_in_field<T>(
+    start: HexCoord<T>,
+    goal: HexCoord<T>,
+    max_cost: u32,
+    field: &impl HexField<T>,
+) -> Option<(Vec<HexCoord<T>>, u32)>
+where
+    T: PrimInt + Signed + Hash,
+{
+    let guide = |hex: HexCoord<T>| {
+        u32::try_from(hex.step_distance(goal)).unwrap_or(u32::MAX)
+    };
+    best_path(start, goal, max_cost, field, guide)
+}
+
+// Search from `start` for `goal` in order of cost so far
+// plus `guide`, which must never overestimate the remaining
+// cost. With a zero guide this is Dijkstra's algorithm.
+fn best_path<T, G>(
+    start: HexCoord<T>,
+    goal: HexCoord<T>,
+    max_cost: u32,
+    field: &impl HexField<T>,
+    guide: G,
+) -> Option<(Vec<HexCoord<T>>, u32)>
+where
+    T: PrimInt + Signed + Hash,
+    G: Fn(HexCoord<T>) -> u32,
+{
+    let mut best = HexMap::new();
+    let mut came_from = HexMap::new();
+    best.insert(start, 0u32);
+    let mut open = BinaryHeap::new();
+    open.push(Reverse((guide(start), 0u32, start)));
+    while let Some(Reverse((_, c, hex))) = open.pop() {
+        if hex == goal {
+            let mut path = vec![hex];
+            let mut h = hex;
+            while let Some(&prev) = came_from.get(h) {
+                path.push(prev);
+                h = prev;
+            }
+            path.reverse();
+            return Some((path, c));
+        }
+        // Skip entries superseded by a cheaper route.
+        if matches!(best.get(hex), Some(&b) if b < c) {
+            continue;
+        }
+        for n in hex.neighbors() {
+            let total =
+                field.entry_cost(n).and_then(|e| c.checked_add(e));
+            let total = match total {
+                Some(total) if total <= max_cost => total,
+                _ => continue,
+            };
+            if matches!(best.get(n), Some(&b) if b <= total) {
+                continue;
+            }
+            best.insert(n, total);
+            came_from.insert(n, hex);
+            open.push(Reverse((
+                total.saturating_add(guide(n)),
+                total,
+                n,
+            )));
+        }
+    }
+    None
+}
+
+#[test]
+fn test_dijkstra_astar() {
+    let start = HexCoord::new(0i32, 0i32);
+    let goal = HexCoord::new(4, 1);
+    let flat = |_| Some(1);
+    let (path, c) = dijkstra(start, goal, 100, flat).unwrap();
+    assert_eq!(start.step_distance(goal) as u32, c);
+    assert_eq!(c as usize + 1, path.len());
+    assert_eq!((start, goal), (path[0], path[path.len() - 1]));
+    assert!(is_contiguous(&path));
+    assert_eq!(Some(c), astar(start, goal, 100, flat).map(|(_, c)| c));
+    assert_eq!(None, dijkstra(start, goal, c - 1, flat));
+    assert_eq!(None, astar(start, goal, c - 1, flat));
+    assert_eq!(Some((vec![start], 0)), astar(start, start, 0, flat));
+
+    // A wall with one gap, and a swamp in front of the gap
+    // worth going around.
+    let wall: HexSet<i32> = (-5..=5)
+        .filter(|&r| r != 3)
+        .map(|r| HexCoord::new(2, r))
+        .collect();
+    let terrain = |hex: HexCoord<i32>| {
+        if wall.contains(hex) {
+            None
+        } else if hex == HexCoord::new(1, 2) {
+            Some(10)
+        } else {
+            Some(1)
+        }
+    };
+    let (path, c) = dijkstra(start, goal, 100, terrain).unwrap();
+    let (apath, ac) = astar(start, goal, 100, terrain).unwrap();
+    assert_eq!(c, ac);
+    for p in &[&path, &apath] {
+        assert!(is_contiguous(p));
+        assert!(p.contains(&HexCoord::new(2, 3)));
+        assert!(!p.contains(&HexCoord::new(1, 2)));
+        let total: u32 =
+            p[1..].iter().map(|&h| terrain(h).unwrap()).sum();
+        assert_eq!(c, total);
+    }
+    let sealed = |hex: HexCoord<i32>| {
+        if hex.q == 2 {
+            None
+        } else {
+            Some(1)
+        }
+    };
+    assert_eq!(None, dijkstra(start, goal, 50, sealed));
+    assert_eq!(None, astar(start, goal, 50, sealed));
+}
+
+#[test]
+fn test_field_searches() {
+    struct World {
+        walls: HexSet<i32>,
+        swamp: HexSet<i32>,
+    }
+
+    impl HexField<i32> for World {
+        fn cost(&self, hex: HexCoord<i32>) -> Option<u32> {
+            if self.swamp.contains(hex) {
+                Some(3)
+            } else {
+                Some(1)
+            }
+        }
+
+        fn passable(&self, hex: HexCoord<i32>) -> bool {
+            !self.walls.contains(hex)
+        }
+    }
+
+    let start = HexCoord::new(0i32, 0i32);
+    let world = World {
+        walls: start.neighbors().skip(1).collect(),
+        swamp: vec![start.neighbor(Direction::NE)]
+            .into_iter()
+            .collect(),
+    };
+    let reached = reachable_in_field(start, 2, 10, &world);
+    assert_eq!(5, reached.len());
+    assert_eq!(Some(&3), reached.get(start.neighbor(Direction::NE)));
+    assert!(reached.iter().all(|(&h, _)| world.passable(h)));
+
+    let as_closure = |hex| {
+        if world.passable(hex) {
+            world.cost(hex)
+        } else {
+            None
+        }
+    };
+    let wrapped = reachable_budgeted(start, 2, 10, as_closure);
+    assert_eq!(reached.len(), wrapped.len());
+    for (&hex, c) in reached.iter() {
+        assert_eq!(Some(c), wrapped.get(hex));
+    }
+
+    // The same world for both path searches: the only way
+    // out is through the swamp.
+    let goal = HexCoord::new(-2, 3);
+    let (path, c) =
+        dijkstra_in_field(start, goal, 100, &world).unwrap();
+    assert_eq!(start.neighbor(Direction::NE), path[1]);
+    assert!(path.iter().all(|&h| world.passable(h)));
+    let (apath, ac) = astar_in_field(start, goal, 100, &world).unwrap();
+    assert_eq!(c, ac);
+    assert_eq!(path.len(), apath.len());
+    let far = reachable_in_field(start, 100, c, &world);
+    assert_eq!(Some(&c), far.get(goal));
+    assert_eq!(Some((path, c)), dijkstra(start, goal, 100, as_closure));
+}
+
+/// Each hex of `region` labeled with the index in `seeds` of
+/// the seed nearest to it by step distance, as in a Voronoi
+/// partition. A hex equidistant from several seeds goes to
+/// the one with the lowest index. The result is empty if
+/// `seeds` is.
+pub fn assign_nearest<T, I>(
+    seeds: &[HexCoord<T>],
+    region: I,
+) -> HexMap<T, usize>
+where
+    T: Num + Copy + Hash + Eq + PartialOrd,
+    I: IntoIterator<Item = HexCoord<T>>,
+{
+    let mut labels = HexMap::new();
+    if seeds.is_empty() {
+        return labels;
+    }
+    for hex in region {
+        // `min_by` keeps the first of equal elements.
+        let (nearest, _) = seeds
+            .iter()
+            .enumerate()
+            .map(|(i, &s)| (i, s.distance(hex)))
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .unwrap();
+        labels.insert(hex, nearest);
+    }
+    labels
+}
+
+#[test]
+fn test_assign_nearest() {
+    let h = HexCoord::new;
+    let center = h(0i32, 0i32);
+    let seeds = [h(-2, 0), h(2, 0)];
+    let labels = assign_nearest(&seeds, shapes::range(center, 4));
+    assert_eq!(61, labels.len());
+    for (&hex, &i) in labels.iter() {
+        let (da, db) = (seeds[0].distance(hex), seeds[1].distance(hex));
+        if da == db {
+            assert_eq!(0, i);
+        } else {
+            assert_eq!(if da < db { 0 } else { 1 }, i);
+        }
+    }
+    assert_eq!(Some(&0), labels.get(center));
+    assert_eq!(Some(&1), labels.get(h(1, 0)));
+    assert_eq!(Some(&0), labels.get(h(-1, 0)));
+
+    let swapped = assign_nearest(&[seeds[1], seeds[0]], vec![center]);
+    assert_eq!(Some(&0), swapped.get(center));
+    assert!(assign_nearest(&[], vec![center]).is_empty());
+}
+
+/// The hexes enclosed by `loop_hexes`: those not in
+/// `loop_hexes` from which no path of neighboring hexes
+/// avoiding `loop_hexes` escapes to infinity. The result is
+/// empty if `loop_hexes` does not enclose anything.
+pub fn enclosed_area<T>(loop_hexes: &HexSet<T>) -> HexSet<T>
+where
+    T: PrimInt + Signed + Hash,
+{
+    let mut hexes = loop_hexes.iter();
+    let first = match hexes.next() {
+        Some(&h) => h,
+        None => return HexSet::new(),
+    };
+    let (mut q0, mut q1, mut r0, mut r1) =
+        (first.q, first.q, first.r, first.r);
+    for h in hexes {
+        q0 = q0.min(h.q);
+        q1 = q1.max(h.q);
+        r0 = r0.min(h.r);
+        r1 = r1.max(h.r);
+    }
+    // A margin of one hex around the loop's bounds is all
+    // outside and connected, so flooding from its corner
+    // reaches everything surrounding the loop.
+    let one = T::one();
+    let bounds = HexBounds::new(
+        q0 - one..q1 + one + one,
+        r0 - one..r1 + one + one,
+    );
+    let start = HexCoord::new(q0 - one, r0 - one);
+    let mut outside = HexSet::new();
+    outside.insert(start);
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+    while let Some(hex) = queue.pop_front() {
+        for n in hex.neighbors() {
+            if bounds.contains(n)
+                && !loop_hexes.contains(n)
+                && outside.insert(n)
+            {
+                queue.push_back(n);
+            }
+        }
+    }
+    (0..bounds.len())
+        .filter_map(|i| bounds.from_index(i))
+        .filter(|&h| !loop_hexes.contains(h) && !outside.contains(h))
+        .collect()
+}
+
+#[test]
+fn test_enclosed_area() {
+    let center = HexCoord::new(3i32, -2i32);
+    let ring: HexSet<i32> = shapes::ring(center, 2).collect();
+    let inside = enclosed_area(&ring);
+    let expected: HexSet<i32> = shapes::ring(center, 1)
+        .chain(std::iter::once(center))
+        .collect();
+    assert_eq!(expected, inside);
+
+    let ring: HexSet<i32> = shapes::ring(center, 1).collect();
+    let inside = enclosed_area(&ring);
+    assert_eq!(vec![&center], inside.iter().collect::<Vec<_>>());
+
+    // An open arc encloses nothing.
+    let arc: HexSet<i32> = shapes::ring(center, 3).skip(1).collect();
+    assert!(enclosed_area(&arc).is_empty());
+    assert!(enclosed_area(&HexSet::<i32>::new()).is_empty());
+}
+
+/// Continuous positions along a path of hexes, for smooth
+/// movement: the path is traced through the Cartesian
+/// centers of its hexes in order, with each step taking an
+/// equal share of the parameter range `0..=1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathSampler<T> {
+    path: Vec<HexCoord<T>>,
+}
+
+impl<T: Num + Clone> PathSampler<T> {
+    /// Make a sampler for `path`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` is empty.
+    pub fn new(path: Vec<HexCoord<T>>) -> Self {
+        assert!(!path.is_empty(), "empty sampler path");
+        PathSampler { path }
+    }
+
+    /// The hexes of the path.
+    pub fn path(&self) -> &[HexCoord<T>] {
+        &self.path
+    }
+
+    /// Cartesian position at parameter `t`: the center of
+    /// the first hex at `0`, of the last at `1`, and of hex
+    /// `i` at `i / (len - 1)`, interpolating linearly
+    /// between. `t` outside `0..=1` is clamped.
+    pub fn sample<U: Float>(&self, t: U) -> (U, U)
+    where
+        T: Into<U>,
+    {
+        let steps = self.path.len() - 1;
+        let center = |i: usize| self.path[i].clone().cartesian_center();
+        if steps == 0 {
+            return center(0);
+        }
+        let s =
+            t.max(U::zero()).min(U::one()) * U::from(steps).unwrap();
+        let i = s.floor().to_usize().unwrap().min(steps - 1);
+        let f = s - U::from(i).unwrap();
+        let ((x0, y0), (x1, y1)) = (center(i), center(i + 1));
+        (x0 + (x1 - x0) * f, y0 + (y1 - y0) * f)
+    }
+}
+
+#[test]
+fn test_path_sampler() {
+    let start = HexCoord::new(0i32, 0i32);
+    let path = start.line_to(HexCoord::new(3, -1));
+    let sampler = PathSampler::new(path.clone());
+    let n = path.len();
+    assert_eq!(5, n);
+    let center = |h: HexCoord<i32>| h.cartesian_center::<f64>();
+    assert_eq!(center(path[0]), sampler.sample(0.0));
+    assert_eq!(center(path[n - 1]), sampler.sample(1.0));
+    assert_eq!(center(path[n - 1]), sampler.sample(1.5));
+    assert_eq!(center(path[0]), sampler.sample(-0.5));
+
+    // Segment boundaries land on hex centers, and the
+    // position is continuous across them.
+    for (i, &hex) in path.iter().enumerate().take(n - 1).skip(1) {
+        let t = i as f64 / (n - 1) as f64;
+        let (x, y) = sampler.sample(t);
+        let (cx, cy) = center(hex);
+        assert!((x - cx).abs() < 1e-12 && (y - cy).abs() < 1e-12);
+        let (bx, by) = sampler.sample(t - 1e-9);
+        let (ax, ay) = sampler.sample(t + 1e-9);
+        assert!((ax - bx).abs() < 1e-6 && (ay - by).abs() < 1e-6);
+    }
+
+    let (x, y) = sampler.sample(0.375);
+    let (x1, y1) = center(path[1]);
+    let (x2, y2) = center(path[2]);
+    assert!((x - (x1 + x2) / 2.0).abs() < 1e-12);
+    assert!((y - (y1 + y2) / 2.0).abs() < 1e-12);
+
+    let single = PathSampler::new(vec![start]);
+    assert_eq!(center(start), single.sample(0.7));
+}
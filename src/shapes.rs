@@ -0,0 +1,934 @@
+//! Iterators over regions of the hex grid: disks, sectors
+//! and the like.
+
+use std::cmp::{max, min};
+use std::hash::Hash;
+
+use num::{PrimInt, Signed, ToPrimitive};
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::*;
+
+/// Iterator over all hexes within distance `radius` of
+/// `center`, including `center` itself. A negative radius
+/// yields nothing.
+pub fn range<T>(
+    center: HexCoord<T>,
+    radius: T,
+) -> impl Iterator<Item = HexCoord<T>>
+where
+    T: PrimInt + Signed,
+{
+    let (lo, hi) = offset_bounds(radius);
+    num::range_inclusive(lo, hi).flat_map(move |dq| {
+        let lo = max(-radius, dq - radius);
+        let hi = min(radius, dq + radius);
+        num::range_inclusive(lo, hi)
+            .map(move |dr| HexCoord::new(center.q + dq, center.r + dr))
+    })
+}
+
+// Bounds of the offsets within `radius` along an axis:
+// empty for a negative radius, which might not be
+// negatable.
+fn offset_bounds<T: PrimInt + Signed>(radius: T) -> (T, T) {
+    if radius.is_negative() {
+        (T::one(), T::zero())
+    } else {
+        (-radius, radius)
+    }
+}
+
+#[test]
+fn test_range() {
+    let center = HexCoord::new(2i32, -3i32);
+    for radius in 0..5 {
+        let hexes: Vec<_> = range(center, radius).collect();
+        assert_eq!(disk_area(radius as usize), hexes.len());
+        assert!(hexes.iter().all(|&h| center.distance(h) <= radius));
+    }
+    assert_eq!(0, range(center, -1).count());
+}
+
+/// Number of hexes yielded by `range()` of the given
+/// radius: `3 * radius * (radius + 1) + 1`.
+pub const fn disk_area(radius: usize) -> usize {
+    3 * radius * (radius + 1) + 1
+}
+
+/// Number of hexes in a ring of the given radius: `6 *
+/// radius`. This is the number yielded by `ring()` for any
+/// positive radius, but `ring()` of radius `0` yields the
+/// center itself.
+pub const fn ring_len(radius: usize) -> usize {
+    6 * radius
+}
+
+#[test]
+fn test_disk_area_ring_len() {
+    const AREA: usize = disk_area(2);
+    assert_eq!(19, AREA);
+    let areas = [1, 7, 19, 37, 61, 91];
+    let lens = [0, 6, 12, 18, 24, 30];
+    let center = HexCoord::new(0i32, 0i32);
+    for radius in 0..6 {
+        assert_eq!(areas[radius], disk_area(radius));
+        assert_eq!(lens[radius], ring_len(radius));
+        assert_eq!(
+            disk_area(radius),
+            range(center, radius as i32).count()
+        );
+        if radius > 0 {
+            let ring = ring(center, radius as i32);
+            assert_eq!(ring_len(radius), ring.count());
+        }
+    }
+}
+
+/// Each hex within distance `radius` of `center`, as in
+/// `range()`, mapped to the index of its ring: its step
+/// distance from `center`.
+pub fn ring_index_map<T>(
+    center: HexCoord<T>,
+    radius: T,
+) -> HexMap<T, usize>
+where
+    T: PrimInt + Signed + Hash,
+{
+    let mut map = HexMap::new();
+    for hex in range(center, radius) {
+        map.insert(hex, center.step_distance(hex));
+    }
+    map
+}
+
+#[test]
+fn test_ring_index_map() {
+    let center = HexCoord::new(-1i32, 4i32);
+    let map = ring_index_map(center, 3);
+    assert_eq!(37, map.len());
+    assert_eq!(Some(&0), map.get(center));
+    for k in 1..=3 {
+        for hex in ring(center, k) {
+            assert_eq!(Some(&(k as usize)), map.get(hex));
+        }
+    }
+    assert!(!map.contains(HexCoord::new(-1, 8)));
+    assert!(ring_index_map(center, -1).is_empty());
+}
+
+/// Iterator over the hexes at distance exactly `radius`
+/// from `center`, counterclockwise starting with the hex
+/// `radius` steps northeast of `center`. A zero radius
+/// yields just `center`; a negative one yields nothing.
+pub fn ring<T>(
+    center: HexCoord<T>,
+    radius: T,
+) -> impl Iterator<Item = HexCoord<T>>
+where
+    T: PrimInt + Signed,
+{
+    let mut hexes = Vec::new();
+    if radius.is_zero() {
+        hexes.push(center);
+    } else if radius.is_positive() {
+        let mut hex =
+            HexCoord::new(center.q + radius, center.r + radius);
+        for i in 0..6 {
+            let d = DIRECTIONS[(i + 2) % 6];
+            for _ in num::range(T::zero(), radius) {
+                hexes.push(hex);
+                hex = hex.neighbor(d);
+            }
+        }
+    }
+    hexes.into_iter()
+}
+
+#[test]
+fn test_ring() {
+    let center = HexCoord::new(3i32, -1i32);
+    assert_eq!(vec![center], ring(center, 0).collect::<Vec<_>>());
+    assert_eq!(0, ring(center, -2).count());
+    assert_eq!(Some(HexCoord::new(4, 0)), ring(center, 1).next());
+    for radius in 1..5 {
+        let hexes: Vec<_> = ring(center, radius).collect();
+        assert_eq!(6 * radius as usize, hexes.len());
+        assert!(hexes.iter().all(|&h| center.distance(h) == radius));
+        let start = HexCoord::new(center.q + radius, center.r + radius);
+        assert_eq!(start, hexes[0]);
+        let n = hexes.len();
+        for i in 0..n {
+            assert_eq!(1, hexes[i].distance(hexes[(i + 1) % n]));
+        }
+    }
+}
+
+/// Iterator over the hexes at distance exactly `n` from
+/// `center_a` that are also within distance `m` of
+/// `center_b`, in the order of `ring()`.
+pub fn ring_within<T>(
+    center_a: HexCoord<T>,
+    n: T,
+    center_b: HexCoord<T>,
+    m: T,
+) -> impl Iterator<Item = HexCoord<T>>
+where
+    T: PrimInt + Signed,
+{
+    ring(center_a, n).filter(move |&h| h.distance(center_b) <= m)
+}
+
+#[test]
+fn test_ring_within() {
+    let a = HexCoord::new(0i32, 0i32);
+    let b = HexCoord::new(3i32, 1i32);
+    let (n, m) = (3, 2);
+    let result: HexSet<i32> = ring_within(a, n, b, m).collect();
+    let expected: HexSet<i32> = range(a, n)
+        .filter(|&h| a.distance(h) == n)
+        .filter(|&h| range(b, m).any(|g| g == h))
+        .collect();
+    assert!(!result.is_empty());
+    assert_eq!(expected, result);
+
+    let far = HexCoord::new(20, 0);
+    assert_eq!(0, ring_within(a, n, far, m).count());
+}
+
+/// Iterator over the hexes whose distance from `center` is
+/// from `inner` through `outer` inclusive: the hexes of
+/// `range(center, outer)` not in `range(center, inner - 1)`.
+/// The rings are given in turn from the inside out, each in
+/// the order of `ring()`. A non-positive `inner` yields all
+/// of `range(center, outer)`, and an `inner` beyond `outer`
+/// yields nothing.
+pub fn annulus<T>(
+    center: HexCoord<T>,
+    inner: T,
+    outer: T,
+) -> impl Iterator<Item = HexCoord<T>>
+where
+    T: PrimInt + Signed,
+{
+    num::range_inclusive(max(inner, T::zero()), outer)
+        .flat_map(move |k| ring(center, k))
+}
+
+#[test]
+fn test_annulus() {
+    let center = HexCoord::new(-2i32, 1i32);
+    let disk = |r: i32| if r < 0 { 0 } else { disk_area(r as usize) };
+    for outer in 0..5 {
+        for inner in 0..=outer {
+            let band: HexSet<i32> =
+                annulus(center, inner, outer).collect();
+            assert_eq!(disk(outer) - disk(inner - 1), band.len());
+            assert!(band.iter().all(|&h| {
+                let d = center.distance(h);
+                inner <= d && d <= outer
+            }));
+        }
+        let full: HexSet<i32> = range(center, outer).collect();
+        let band: HexSet<i32> = annulus(center, 0, outer).collect();
+        assert_eq!(full, band);
+        assert_eq!(0, annulus(center, outer + 1, outer).count());
+    }
+    assert_eq!(disk(3), annulus(center, -2, 3).count());
+}
+
+/// The hex nearest `center`, within distance `max_radius`,
+/// satisfying `pred`: the rings around `center` are searched
+/// outward in turn, each in the order of `ring()`, stopping
+/// at the first match. `None` if no hex within `max_radius`
+/// matches.
+pub fn find_spiral<T, F>(
+    center: HexCoord<T>,
+    max_radius: T,
+    pred: F,
+) -> Option<HexCoord<T>>
+where
+    T: PrimInt + Signed,
+    F: Fn(HexCoord<T>) -> bool,
+{
+    num::range_inclusive(T::zero(), max_radius)
+        .flat_map(|radius| ring(center, radius))
+        .find(|&h| pred(h))
+}
+
+#[test]
+fn test_find_spiral() {
+    let center = HexCoord::new(1i32, -1i32);
+    let far = |h: HexCoord<i32>| center.distance(h) >= 2;
+    let found = find_spiral(center, 3, far).unwrap();
+    assert_eq!(2, center.distance(found));
+    assert_eq!(ring(center, 2).next(), Some(found));
+
+    let target = HexCoord::new(-1, -2);
+    assert_eq!(Some(target), find_spiral(center, 2, |h| h == target));
+    assert_eq!(None, find_spiral(center, 1, |h| h == target));
+    assert_eq!(Some(center), find_spiral(center, 0, |_| true));
+}
+
+// Cube offset of the neighbor in direction `d`.
+fn cube_delta<T>(d: Direction) -> HexCubeCoord<T>
+where
+    T: PrimInt + Signed,
+{
+    let (dq, dr) = DIRECTION_DELTAS[d as usize];
+    let c = |v: i8| T::from(v).unwrap();
+    HexCubeCoord::new_unchecked(c(dq), c(dr - dq), c(-dr))
+}
+
+/// Iterator over the cubes within distance `radius` of
+/// `center`: `range()` in cube coordinates, in the same
+/// order, computed without converting to axial.
+pub fn cube_range<T>(
+    center: HexCubeCoord<T>,
+    radius: T,
+) -> impl Iterator<Item = HexCubeCoord<T>>
+where
+    T: PrimInt + Signed,
+{
+    let (lo, hi) = offset_bounds(radius);
+    num::range_inclusive(lo, hi).flat_map(move |dx| {
+        let lo = max(-radius, dx - radius);
+        let hi = min(radius, dx + radius);
+        // Axial `r` is `-z`, so `z` runs downward to match
+        // the order of `range()`.
+        num::range_inclusive(lo, hi).map(move |dr| {
+            let delta = HexCubeCoord::new_unchecked(dx, dr - dx, -dr);
+            center + delta
+        })
+    })
+}
+
+/// Iterator over the cubes at distance exactly `radius`
+/// from `center`: `ring()` in cube coordinates, in the same
+/// order, found by adding cube direction offsets.
+pub fn cube_ring<T>(
+    center: HexCubeCoord<T>,
+    radius: T,
+) -> impl Iterator<Item = HexCubeCoord<T>>
+where
+    T: PrimInt + Signed,
+{
+    let mut cubes = Vec::new();
+    if radius.is_zero() {
+        cubes.push(center);
+    } else if radius.is_positive() {
+        let (x, y, z) = cube_delta::<T>(Direction::NE).coords();
+        let start = HexCubeCoord::new_unchecked(
+            x * radius,
+            y * radius,
+            z * radius,
+        );
+        let mut cube = center + start;
+        for i in 0..6 {
+            let delta = cube_delta(DIRECTIONS[(i + 2) % 6]);
+            for _ in num::range(T::zero(), radius) {
+                cubes.push(cube);
+                cube = cube + delta;
+            }
+        }
+    }
+    cubes.into_iter()
+}
+
+/// Iterator over the cubes within distance `radius` of
+/// `center`, ring by ring from the inside out, each ring in
+/// the order of `cube_ring()`: the order searched by
+/// `find_spiral()`, and that of `annulus(center, 0,
+/// radius)` in axial coordinates.
+pub fn cube_spiral<T>(
+    center: HexCubeCoord<T>,
+    radius: T,
+) -> impl Iterator<Item = HexCubeCoord<T>>
+where
+    T: PrimInt + Signed,
+{
+    num::range_inclusive(T::zero(), radius)
+        .flat_map(move |k| cube_ring(center, k))
+}
+
+#[test]
+fn test_cube_shapes() {
+    let axial = HexCoord::new(3i32, -7i32);
+    let center = HexCubeCoord::from(axial);
+    let cubes = |v: Vec<HexCoord<i32>>| -> Vec<HexCubeCoord<i32>> {
+        v.into_iter().map(HexCubeCoord::from).collect()
+    };
+    for radius in -1..5 {
+        assert_eq!(
+            cubes(range(axial, radius).collect()),
+            cube_range(center, radius).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            cubes(ring(axial, radius).collect()),
+            cube_ring(center, radius).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            cubes(annulus(axial, 0, radius).collect()),
+            cube_spiral(center, radius).collect::<Vec<_>>()
+        );
+    }
+    assert!(cube_range(center, 3).all(|c| {
+        let (x, y, z) = c.coords();
+        x + y + z == 0
+    }));
+}
+
+/// Iterator over the hexes of `ring(center, radius)`, in
+/// the same order, each paired with a direction pointing
+/// away from `center`. The six corner hexes of the ring face
+/// straight out, along the direction used to reach them from
+/// `center`. Each other hex faces the direction of the
+/// nearer of the two corners bounding its side; a hex
+/// exactly midway between two corners faces the direction of
+/// the earlier one. A radius that is not positive yields
+/// nothing, since the center has no outward direction.
+pub fn ring_with_facing<T>(
+    center: HexCoord<T>,
+    radius: T,
+) -> impl Iterator<Item = (HexCoord<T>, Direction)>
+where
+    T: PrimInt + Signed,
+{
+    let mut hexes = Vec::new();
+    if radius.is_positive() {
+        let two = T::one() + T::one();
+        let mut hex =
+            HexCoord::new(center.q + radius, center.r + radius);
+        for i in 0..6 {
+            let d = DIRECTIONS[(i + 2) % 6];
+            for j in num::range(T::zero(), radius) {
+                let facing = if j * two <= radius { i } else { i + 1 };
+                hexes.push((hex, DIRECTIONS[facing % 6]));
+                hex = hex.neighbor(d);
+            }
+        }
+    }
+    hexes.into_iter()
+}
+
+#[test]
+fn test_ring_with_facing() {
+    use Direction::*;
+    let center = HexCoord::new(-2i32, 1i32);
+    assert_eq!(0, ring_with_facing(center, 0).count());
+    for radius in 1..5 {
+        let faced: Vec<_> = ring_with_facing(center, radius).collect();
+        let hexes: Vec<_> = ring(center, radius).collect();
+        assert_eq!(
+            hexes,
+            faced.iter().map(|&(h, _)| h).collect::<Vec<_>>()
+        );
+        for &(h, d) in &faced {
+            assert_eq!(radius + 1, center.distance(h.neighbor(d)));
+        }
+    }
+    let corners: Vec<_> =
+        ring_with_facing(center, 3).step_by(3).collect();
+    for (&(h, d), &dirn) in corners.iter().zip(&DIRECTIONS) {
+        let mut corner = center;
+        for _ in 0..3 {
+            corner = corner.neighbor(dirn);
+        }
+        assert_eq!((corner, dirn), (h, d));
+    }
+    let faced: Vec<_> = ring_with_facing(center, 2).collect();
+    assert_eq!(NE, faced[1].1);
+    let faced: Vec<_> = ring_with_facing(center, 3).collect();
+    assert_eq!(NE, faced[1].1);
+    assert_eq!(N, faced[2].1);
+}
+
+/// Iterator over a `cols × rows` rectangle of hexes with
+/// offset coordinates `(0, 0)` through `(cols - 1, rows -
+/// 1)`, each paired with its axial coordinate. Hexes are
+/// yielded in row-major order: the whole top row first,
+/// west to east.
+pub fn rectangle_offset<T>(
+    cols: T,
+    rows: T,
+    kind: OffsetKind,
+) -> impl Iterator<Item = (OffsetCoord<T>, HexCoord<T>)>
+where
+    T: PrimInt + Signed,
+{
+    num::range(T::zero(), rows).flat_map(move |row| {
+        num::range(T::zero(), cols).map(move |col| {
+            let off = OffsetCoord::new(col, row);
+            (off, off.to_hex(kind))
+        })
+    })
+}
+
+#[test]
+fn test_rectangle_offset() {
+    for &kind in &[OffsetKind::OddQ, OffsetKind::EvenQ] {
+        let (cols, rows) = (5i32, 3i32);
+        let cells: Vec<_> =
+            rectangle_offset(cols, rows, kind).collect();
+        assert_eq!((cols * rows) as usize, cells.len());
+        for (i, &(off, hex)) in cells.iter().enumerate() {
+            let i = i as i32;
+            assert_eq!(OffsetCoord::new(i % cols, i / cols), off);
+            assert_eq!(off, OffsetCoord::from_hex(hex, kind));
+        }
+        let hexes: HexSet<i32> =
+            cells.iter().map(|&(_, h)| h).collect();
+        assert_eq!(cells.len(), hexes.len());
+    }
+    assert_eq!(0, rectangle_offset(0, 4, OffsetKind::OddQ).count());
+}
+
+// Signed area spanned by cube vectors `a` and `b`: the
+// component of `a × b` along `(1, 1, 1)`. Positive when `b`
+// is counterclockwise of `a`.
+fn cross<T: Num + Copy>(a: HexCubeCoord<T>, b: HexCubeCoord<T>) -> T {
+    let (ax, ay, az) = a.coords();
+    let (bx, by, bz) = b.coords();
+    (ay * bz - az * by) + (az * bx - ax * bz) + (ax * by - ay * bx)
+}
+
+/// Iterator over the hexes within distance `radius` of
+/// `center` lying in the sector swept counterclockwise from
+/// direction `from` to direction `to`, bounding rays
+/// included. When `from == to` the sector is the full
+/// circle, giving the same hexes as `range()`.
+///
+/// Membership is decided by the half-planes of the two
+/// bounding direction vectors in cube space.
+pub fn wedge<T>(
+    center: HexCoord<T>,
+    radius: T,
+    from: Direction,
+    to: Direction,
+) -> impl Iterator<Item = HexCoord<T>>
+where
+    T: PrimInt + Signed,
+{
+    let origin = HexCoord::new(T::zero(), T::zero());
+    let a = HexCubeCoord::from(origin.neighbor(from));
+    let b = HexCubeCoord::from(origin.neighbor(to));
+    let span = (to as usize + 6 - from as usize) % 6;
+    range(center, radius).filter(move |&h| {
+        let offset = HexCoord::new(h.q - center.q, h.r - center.r);
+        let p = HexCubeCoord::from(offset);
+        match span {
+            0 => true,
+            1..=3 => {
+                cross(a, p) >= T::zero() && cross(p, b) >= T::zero()
+            }
+            _ => !(cross(b, p) > T::zero() && cross(p, a) > T::zero()),
+        }
+    })
+}
+
+#[test]
+fn test_wedge() {
+    use Direction::*;
+    let center = HexCoord::new(1i32, 1i32);
+    let radius = 4;
+    let disk: Vec<_> = range(center, radius).collect();
+
+    // A 60° wedge holds a sixth of the disk, plus the extra
+    // bounding ray shared with its neighbor.
+    let sixth: Vec<_> = wedge(center, radius, NE, N).collect();
+    let n = radius as usize;
+    assert_eq!((n + 1) * (n + 2) / 2, sixth.len());
+    assert!(sixth.contains(&center.neighbor(NE)));
+    assert!(sixth.contains(&center.neighbor(N)));
+    assert!(!sixth.contains(&center.neighbor(NW)));
+
+    // Six 60° wedges cover the disk.
+    let dirns = [NE, N, NW, SW, S, SE];
+    for &h in &disk {
+        assert!((0..6).any(|i| {
+            wedge(center, radius, dirns[i], dirns[(i + 1) % 6])
+                .any(|w| w == h)
+        }));
+    }
+
+    // A reflex wedge and its complement cover the disk,
+    // overlapping only on their shared bounding rays.
+    let reflex: Vec<_> = wedge(center, radius, N, NE).collect();
+    assert!(disk
+        .iter()
+        .all(|h| sixth.contains(h) || reflex.contains(h)));
+    let shared = reflex.iter().filter(|h| sixth.contains(h)).count();
+    assert_eq!(2 * n + 1, shared);
+
+    let full: Vec<_> = wedge(center, radius, S, S).collect();
+    assert_eq!(disk, full);
+}
+
+/// The smallest convex region containing `hexes`, where a
+/// region is convex when it contains `line_to()` between
+/// any two of its members (in either direction, since
+/// rounding ties can make the two lines differ). Whenever a
+/// hex joins the hull, the lines from it to every earlier
+/// member are added, until no new hexes appear; this takes
+/// time quadratic in the size of the hull.
+pub fn convex_hull<T>(hexes: &HexSet<T>) -> HexSet<T>
+where
+    T: PrimInt + Signed + Hash,
+{
+    let mut hull = HexSet::new();
+    let mut members = Vec::new();
+    let mut pending: Vec<HexCoord<T>> = hexes.iter().cloned().collect();
+    while let Some(hex) = pending.pop() {
+        if !hull.insert(hex) {
+            continue;
+        }
+        for &m in &members {
+            for h in hex.line_to(m).into_iter().chain(m.line_to(hex)) {
+                if !hull.contains(h) {
+                    pending.push(h);
+                }
+            }
+        }
+        members.push(hex);
+    }
+    hull
+}
+
+#[test]
+fn test_convex_hull() {
+    use Direction::*;
+    let a = HexCoord::new(2i32, -1i32);
+    let n = 5;
+    let (mut b, mut c) = (a, a);
+    for _ in 0..n {
+        b = b.neighbor(NE);
+        c = c.neighbor(N);
+    }
+    let corners: HexSet<i32> = vec![a, b, c].into_iter().collect();
+    let hull = convex_hull(&corners);
+    let n = n as usize;
+    assert_eq!((n + 1) * (n + 2) / 2, hull.len());
+    let filled: HexSet<i32> = wedge(a, n as i32, NE, N).collect();
+    assert_eq!(filled, hull);
+    for &(p, q) in &[(a, b), (b, c), (c, a)] {
+        assert!(p.line_to(q).iter().all(|&h| hull.contains(h)));
+    }
+
+    let disk: HexSet<i32> = range(a, 3).collect();
+    assert_eq!(disk, convex_hull(&disk));
+    let ring: HexSet<i32> = ring(a, 3).collect();
+    assert_eq!(disk, convex_hull(&ring));
+    assert!(convex_hull(&HexSet::<i32>::new()).is_empty());
+}
+
+#[test]
+fn test_convex_hull_line_closure() {
+    let h = HexCoord::new;
+    let pair: HexSet<i32> =
+        vec![h(0, 0), h(1, 2)].into_iter().collect();
+    let expected: HexSet<i32> =
+        h(0, 0).line_to(h(1, 2)).into_iter().collect();
+    assert_eq!(3, expected.len());
+    assert_eq!(expected, convex_hull(&pair));
+
+    // Closing a scattered set by brute force: add every line
+    // between members until nothing changes.
+    let scattered: HexSet<i32> =
+        vec![h(0, 0), h(1, 2), h(5, -1), h(3, 4)]
+            .into_iter()
+            .collect();
+    let mut closed = scattered.clone();
+    loop {
+        let members: Vec<_> = closed.iter().cloned().collect();
+        let mut grown = closed.clone();
+        for &a in &members {
+            for &b in &members {
+                for g in a.line_to(b) {
+                    grown.insert(g);
+                }
+            }
+        }
+        if grown.len() == closed.len() {
+            break;
+        }
+        closed = grown;
+    }
+    let hull = convex_hull(&scattered);
+    assert_eq!(closed, hull);
+    let (c, radius) = enclosing_hexagon(&scattered).unwrap();
+    assert!(hull.len() < range(c, radius as i32).count());
+}
+
+/// A hexagonal region `range(center, radius)` containing
+/// all of `hexes`, as `(center, radius)`, for framing them
+/// in a view. `None` if `hexes` is empty. The center is the
+/// hex nearest the centroid of `hexes`, and the radius is
+/// the greatest distance from it to any of them. This is a
+/// quick heuristic, not necessarily the smallest such
+/// hexagon.
+pub fn enclosing_hexagon<T>(
+    hexes: &HexSet<T>,
+) -> Option<(HexCoord<T>, usize)>
+where
+    T: PrimInt + Signed + Hash,
+{
+    if hexes.is_empty() {
+        return None;
+    }
+    let (mut x, mut y, mut z) = (0.0, 0.0, 0.0);
+    let f = |c: T| c.to_f64().expect("coordinate not representable");
+    for &hex in hexes.iter() {
+        let (q, r) = (f(hex.q), f(hex.r));
+        x += q;
+        y += r - q;
+        z -= r;
+    }
+    let n = hexes.len() as f64;
+    let centroid = FractionalCube::new(x / n, y / n, z / n);
+    let center: HexCoord<T> = centroid.round().into();
+    let radius = hexes.iter().map(|&h| center.step_distance(h)).max();
+    radius.map(|radius| (center, radius))
+}
+
+#[test]
+fn test_enclosing_hexagon() {
+    let h = HexCoord::new;
+    let center = h(4i32, -2i32);
+    let disk: HexSet<i32> = range(center, 2).collect();
+    assert_eq!(Some((center, 2)), enclosing_hexagon(&disk));
+
+    let cluster: HexSet<i32> =
+        vec![h(0, 0), h(2, 0), h(2, 2), h(0, 2), h(1, 1), h(6, 1)]
+            .into_iter()
+            .collect();
+    let (c, radius) = enclosing_hexagon(&cluster).unwrap();
+    assert_eq!(h(2, 1), c);
+    assert_eq!(4, radius);
+    let region: HexSet<i32> = range(c, radius as i32).collect();
+    assert!(cluster.iter().all(|&g| region.contains(g)));
+    assert_eq!(None, enclosing_hexagon(&HexSet::<i32>::new()));
+}
+
+/// True if the center of `query` is inside or on the
+/// boundary of the polygon whose vertices are the centers
+/// of `polygon`, given in order around it in either
+/// direction. The polygon is assumed convex: `query` is
+/// tested against the half-plane of each edge, using exact
+/// axial cross products. A boundary hex counts only if its
+/// center is on an edge, as along the hex axes; an empty
+/// polygon contains nothing. A polygon whose vertices are
+/// all one hex contains just that hex, and one whose
+/// vertices are collinear contains the hexes on the segment
+/// between its extreme vertices.
+pub fn contains_hex<T>(
+    polygon: &[HexCoord<T>],
+    query: HexCoord<T>,
+) -> bool
+where
+    T: Num + Copy + PartialOrd,
+{
+    let n = polygon.len();
+    if n == 0 {
+        return false;
+    }
+    let cross = |a: HexCoord<T>, b: HexCoord<T>, c: HexCoord<T>| {
+        (b.q - a.q) * (c.r - a.r) - (b.r - a.r) * (c.q - a.q)
+    };
+    let a = polygon[0];
+    let b = match polygon.iter().find(|&&v| v != a) {
+        Some(&b) => b,
+        None => return query == a,
+    };
+    if polygon.iter().all(|&v| cross(a, b, v) == T::zero()) {
+        if cross(a, b, query) != T::zero() {
+            return false;
+        }
+        // Collinear, so one nonconstant axial component
+        // orders the points along the segment.
+        let along = |v: HexCoord<T>| if a.q != b.q { v.q } else { v.r };
+        let t = along(query);
+        let before = polygon.iter().any(|&v| along(v) <= t);
+        let after = polygon.iter().any(|&v| along(v) >= t);
+        return before && after;
+    }
+    let (mut left, mut right) = (false, false);
+    for i in 0..n {
+        let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+        let cross = cross(a, b, query);
+        left |= cross > T::zero();
+        right |= cross < T::zero();
+    }
+    !(left && right)
+}
+
+#[test]
+fn test_contains_hex() {
+    let h = HexCoord::new;
+    // Edges along the SE, N and SW axes.
+    let triangle = [h(0i32, 0i32), h(4, 0), h(4, 4)];
+    let reversed = [h(4, 4), h(4, 0), h(0, 0)];
+    let inside = |hex| {
+        let c = contains_hex(&triangle, hex);
+        assert_eq!(c, contains_hex(&reversed, hex));
+        c
+    };
+    assert!(inside(h(3, 1)));
+    assert!(inside(h(2, 1)));
+    for &vertex in &triangle {
+        assert!(inside(vertex));
+    }
+    assert!(inside(h(2, 0)));
+    assert!(inside(h(4, 2)));
+    assert!(inside(h(2, 2)));
+    assert!(!inside(h(1, 2)));
+    assert!(!inside(h(5, 2)));
+    assert!(!inside(h(2, -1)));
+    let filled = range(h(2, 2), 5).filter(|&g| inside(g)).count();
+    assert_eq!(15, filled);
+    assert!(!contains_hex(&[], h(0, 0)));
+}
+
+#[test]
+fn test_contains_hex_degenerate() {
+    let h = HexCoord::new;
+    let point = [h(0i32, 0i32)];
+    assert!(contains_hex(&point, h(0, 0)));
+    assert!(!contains_hex(&point, h(9, -4)));
+    assert!(!contains_hex(&point, h(1, 0)));
+    assert!(contains_hex(&[h(3, 1), h(3, 1), h(3, 1)], h(3, 1)));
+    assert!(!contains_hex(&[h(3, 1), h(3, 1)], h(3, 2)));
+
+    let segment = [h(0, 0), h(2, 0)];
+    for q in 0..=2 {
+        assert!(contains_hex(&segment, h(q, 0)));
+    }
+    assert!(!contains_hex(&segment, h(7, 0)));
+    assert!(!contains_hex(&segment, h(-1, 0)));
+    assert!(!contains_hex(&segment, h(1, 1)));
+    // A vertical segment, with a repeated interior vertex.
+    let column = [h(1, -1), h(1, 0), h(1, 0), h(1, 2)];
+    assert!(contains_hex(&column, h(1, 1)));
+    assert!(contains_hex(&column, h(1, -1)));
+    assert!(!contains_hex(&column, h(1, 3)));
+    assert!(!contains_hex(&column, h(1, -2)));
+}
+
+/// The given `hexes` ordered by increasing distance from
+/// `center`. The sort is stable: hexes at equal distance
+/// keep their input order.
+pub fn by_distance<T, I>(
+    center: HexCoord<T>,
+    hexes: I,
+) -> Vec<HexCoord<T>>
+where
+    T: Num + PartialOrd + Clone + ToPrimitive,
+    I: IntoIterator<Item = HexCoord<T>>,
+{
+    let mut hexes: Vec<_> = hexes.into_iter().collect();
+    hexes.sort_by_key(|h| center.clone().step_distance(h.clone()));
+    hexes
+}
+
+#[test]
+fn test_by_distance() {
+    let center = HexCoord::new(-1i32, 2i32);
+    let mut shuffled: Vec<_> = range(center, 3).collect();
+    shuffled.sort_by_key(|h| (h.q * 7 + h.r * 13).rem_euclid(11));
+    let sorted = by_distance(center, shuffled.clone());
+    assert_eq!(shuffled.len(), sorted.len());
+    assert_eq!(center, sorted[0]);
+    for pair in sorted.windows(2) {
+        let d0 = center.distance(pair[0]);
+        let d1 = center.distance(pair[1]);
+        assert!(d0 <= d1);
+        if d0 == d1 {
+            let i0 = shuffled.iter().position(|&h| h == pair[0]);
+            let i1 = shuffled.iter().position(|&h| h == pair[1]);
+            assert!(i0 < i1);
+        }
+    }
+}
+
+/// Parallel `range()`, for spreading per-hex work over
+/// threads with `rayon`. The hexes are the same, though not
+/// in a defined order. Since `range()` is the hexagon of the
+/// given radius, this is also the parallel hexagon; there
+/// is no separate `par_hexagon()`.
+#[cfg(feature = "rayon")]
+pub fn par_range<T>(
+    center: HexCoord<T>,
+    radius: T,
+) -> impl ParallelIterator<Item = HexCoord<T>>
+where
+    T: PrimInt + Signed + Send + Sync,
+{
+    let (lo, hi) = offset_bounds(radius);
+    let columns: Vec<T> = num::range_inclusive(lo, hi).collect();
+    columns.into_par_iter().flat_map_iter(move |dq| {
+        let lo = max(-radius, dq - radius);
+        let hi = min(radius, dq + radius);
+        num::range_inclusive(lo, hi)
+            .map(move |dr| HexCoord::new(center.q + dq, center.r + dr))
+    })
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_range() {
+    let center = HexCoord::new(-3i32, 5i32);
+    for radius in -1..6 {
+        let par: Vec<_> = par_range(center, radius).collect();
+        let seq: HexSet<i32> = range(center, radius).collect();
+        assert_eq!(seq.len(), par.len());
+        assert_eq!(seq, par.into_iter().collect());
+    }
+}
+
+/// Parallel `ring()`. See `par_range()`.
+#[cfg(feature = "rayon")]
+pub fn par_ring<T>(
+    center: HexCoord<T>,
+    radius: T,
+) -> impl ParallelIterator<Item = HexCoord<T>>
+where
+    T: PrimInt + Signed + Send + Sync,
+{
+    let steps = if radius.is_positive() {
+        radius.to_usize().expect("ring radius out of range")
+    } else {
+        0
+    };
+    let len = if radius.is_zero() { 1 } else { ring_len(steps) };
+    let origin = HexCoord::new(T::zero(), T::zero());
+    let mut units = [origin; 6];
+    for (unit, &d) in units.iter_mut().zip(DIRECTIONS.iter()) {
+        *unit = origin.neighbor(d);
+    }
+    // Hex `i` is `i % steps` steps along side `i / steps` of
+    // `ring()`, which starts at corner `radius * units[side]`
+    // and runs in direction `side + 2`.
+    (0..len).into_par_iter().map(move |i| {
+        if steps == 0 {
+            return center;
+        }
+        let (side, along) = (i / steps, i % steps);
+        let along = T::from(along).unwrap();
+        let (corner, run) = (units[side], units[(side + 2) % 6]);
+        HexCoord::new(
+            center.q + radius * corner.q + along * run.q,
+            center.r + radius * corner.r + along * run.r,
+        )
+    })
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_ring() {
+    let center = HexCoord::new(2i32, 2i32);
+    for radius in -1..5 {
+        let par: Vec<_> = par_ring(center, radius).collect();
+        let seq: Vec<_> = ring(center, radius).collect();
+        assert_eq!(seq, par);
+    }
+}
@@ -0,0 +1,110 @@
+//! SVG rendering of hexes and regions. Note that SVG `y`
+//! increases downward, so a `Layout` with negative `y` size
+//! will usually be wanted to keep north at the top.
+
+use std::fmt::{Display, Write};
+use std::hash::Hash;
+
+use num::PrimInt;
+
+use crate::*;
+
+/// SVG path data (the `d` attribute of a `<path>`) for the
+/// outline of `hex` placed by `layout`.
+pub fn to_svg_path<T, U>(hex: HexCoord<T>, layout: &Layout<U>) -> String
+where
+    T: Num + Clone + Into<U>,
+    U: Float + Display,
+{
+    let mut d = String::new();
+    for (i, (x, y)) in layout.polygon_corners(hex).iter().enumerate() {
+        let cmd = if i == 0 { "M" } else { " L" };
+        write!(d, "{} {} {}", cmd, x, y).unwrap();
+    }
+    d.push_str(" Z");
+    d
+}
+
+#[test]
+fn test_to_svg_path() {
+    let path = to_svg_path(
+        HexCoord::new(0i32, 0i32),
+        &Layout::<f64>::default(),
+    );
+    assert!(path.starts_with("M 0.5 0 L 0.25 0.433"));
+    assert!(path.contains(" L -0.5 0 L -0.25 -0.433"));
+    assert_eq!(5, path.matches(" L ").count());
+    assert!(path.ends_with(" Z"));
+
+    let layout = Layout::new((10.0f64, -10.0), (1.0, 2.0));
+    let path = to_svg_path(HexCoord::new(0i32, 0i32), &layout);
+    assert!(path.starts_with("M 6 2 L 3.5 -2.33"));
+}
+
+/// Standalone SVG document drawing the outline of each hex
+/// of `region` placed by `layout`. The view box is fitted
+/// to the region.
+pub fn region_to_svg<T, U>(
+    region: &HexSet<T>,
+    layout: &Layout<U>,
+) -> String
+where
+    T: PrimInt + Hash + Into<U>,
+    U: Float + Display,
+{
+    let mut hexes: Vec<_> = region.iter().copied().collect();
+    hexes.sort();
+
+    let (mut x0, mut y0) = (U::infinity(), U::infinity());
+    let (mut x1, mut y1) = (U::neg_infinity(), U::neg_infinity());
+    for corners in layout.polygon_corners_iter(hexes.iter().copied()) {
+        for &(x, y) in corners.iter() {
+            x0 = x0.min(x);
+            y0 = y0.min(y);
+            x1 = x1.max(x);
+            y1 = y1.max(y);
+        }
+    }
+    if hexes.is_empty() {
+        x0 = U::zero();
+        y0 = U::zero();
+        x1 = U::zero();
+        y1 = U::zero();
+    }
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+        x0,
+        y0,
+        x1 - x0,
+        y1 - y0,
+    )
+    .unwrap();
+    for hex in hexes {
+        writeln!(
+            svg,
+            r#"<path d="{}" fill="none" stroke="black"/>"#,
+            to_svg_path(hex, layout),
+        )
+        .unwrap();
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[test]
+fn test_region_to_svg() {
+    let center = HexCoord::new(0i32, 0i32);
+    let region: HexSet<i32> = shapes::range(center, 1).collect();
+    let layout = Layout::new((1.0f64, -1.0), (0.0, 0.0));
+    let svg = region_to_svg(&region, &layout);
+    assert!(
+        svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg""#)
+    );
+    assert!(svg.contains(r#"viewBox="-1.25 "#));
+    assert_eq!(7, svg.matches("<path ").count());
+    assert!(svg.contains(&to_svg_path(center, &layout)));
+    assert!(svg.ends_with("</svg>\n"));
+}
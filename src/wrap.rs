@@ -0,0 +1,91 @@
+use num::{PrimInt, Signed};
+
+use crate::*;
+
+/// Toroidal board: a `width × height` parallelogram of hexes
+/// in axial coordinates whose opposite edges are glued
+/// together, so that stepping off one side re-enters from
+/// the other. Every hex is equivalent to exactly one hex
+/// with `0 <= q < width` and `0 <= r < height`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WrapGrid<T> {
+    pub width: T,
+    pub height: T,
+}
+
+impl<T: PrimInt + Signed> WrapGrid<T> {
+    /// Make a wrapping grid of the given size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `height` is not positive.
+    pub fn new(width: T, height: T) -> Self {
+        assert!(
+            width.is_positive() && height.is_positive(),
+            "empty wrap grid"
+        );
+        WrapGrid { width, height }
+    }
+
+    /// The canonical hex equivalent to `hex`, with
+    /// coordinates inside the grid.
+    pub fn wrap(&self, hex: HexCoord<T>) -> HexCoord<T> {
+        let wrap1 = |c: T, n: T| ((c % n) + n) % n;
+        HexCoord::new(
+            wrap1(hex.q, self.width),
+            wrap1(hex.r, self.height),
+        )
+    }
+
+    /// Step distance between `a` and `b` by the shortest
+    /// route, which may cross the seams. Once the difference
+    /// of the two hexes is wrapped into the grid, the nearest
+    /// copy of `b` is at most one period away in each
+    /// coordinate.
+    pub fn distance(&self, a: HexCoord<T>, b: HexCoord<T>) -> T {
+        let d = self.wrap(HexCoord::new(b.q - a.q, b.r - a.r));
+        let origin = HexCoord::new(T::zero(), T::zero());
+        let mut best = origin.distance(d);
+        for &dq in &[-self.width, T::zero()] {
+            for &dr in &[-self.height, T::zero()] {
+                let shifted = HexCoord::new(d.q + dq, d.r + dr);
+                best = best.min(origin.distance(shifted));
+            }
+        }
+        best
+    }
+}
+
+#[test]
+fn test_wrap_grid_distance() {
+    let grid = WrapGrid::new(10i32, 8i32);
+    let h = HexCoord::new;
+    assert_eq!(h(3, 5), grid.wrap(h(-7, 13)));
+
+    // Near opposite edges: far apart on the flat board but
+    // neighbors across the seam.
+    let (a, b) = (h(0, 3), h(9, 3));
+    assert_eq!(9, a.distance(b));
+    assert_eq!(1, grid.distance(a, b));
+    let (a, b) = (h(2, 0), h(2, 7));
+    assert_eq!(7, a.distance(b));
+    assert_eq!(1, grid.distance(a, b));
+    let (a, b) = (h(0, 0), h(9, 7));
+    assert_eq!(9, a.distance(b));
+    assert_eq!(1, grid.distance(a, b));
+
+    // Agrees with brute force over many copies of `b`.
+    for aq in 0..10 {
+        for ar in 0..8 {
+            let a = h(aq, ar);
+            let b = h(7, 2);
+            let brute = (-2..=2)
+                .flat_map(|i| (-2..=2).map(move |j| (i, j)))
+                .map(|(i, j)| a.distance(h(b.q + 10 * i, b.r + 8 * j)))
+                .min()
+                .unwrap();
+            assert_eq!(brute, grid.distance(a, b));
+            assert_eq!(grid.distance(b, a), grid.distance(a, b));
+        }
+    }
+}